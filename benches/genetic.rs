@@ -0,0 +1,102 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use dinai::genetic::{Genome, MutationConfig, Population};
+use dinai::math::Matrix;
+use dinai::neuralnet::RecurrentNetwork;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+type Network = RecurrentNetwork<8, 4, 2>;
+
+// A network with the fitness it reached, sized like the players of the game.
+#[derive(Clone)]
+struct Agent {
+    nnet: Network,
+    fitness: f32,
+}
+
+impl Genome for Agent {
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self {
+        Agent {
+            nnet: self.nnet.crossover(&other.nnet, rng),
+            fitness: 0.0,
+        }
+    }
+
+    fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R) {
+        self.nnet.mutate(config, rng);
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        self.nnet.distance(&other.nnet)
+    }
+}
+
+fn population(size: usize, rng: &mut StdRng) -> Population<Agent> {
+    let agents = (0..size)
+        .map(|_| Agent {
+            nnet: Network::new(rng),
+            fitness: rng.gen_range(0.0, 60.0),
+        })
+        .collect();
+
+    let mut population = Population::new(agents);
+    population.set_elite_count(2);
+
+    population
+}
+
+// One generation of the game: every agent thinks for a second of simulated time, then the
+// population is bred.
+fn generation(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let input: Matrix<f32, 1, 8> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let base = population(1000, &mut rng);
+
+    c.bench_function("think 1000 agents for 30 steps", |bench| {
+        bench.iter_batched(
+            || base.clone(),
+            |mut population| {
+                for agent in population.genomes_mut() {
+                    for _ in 0..30 {
+                        agent.nnet.feed(&input);
+                    }
+                }
+                population
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("evolve 1000 agents", |bench| {
+        bench.iter_batched(
+            || (base.clone(), StdRng::seed_from_u64(7)),
+            |(mut population, mut rng)| {
+                population.evolve(&mut rng);
+                population
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("evolve 1000 agents with species", |bench| {
+        bench.iter_batched(
+            || {
+                let mut population = base.clone();
+                population.set_species_threshold(Some(4.0));
+                (population, StdRng::seed_from_u64(7))
+            },
+            |(mut population, mut rng)| {
+                population.evolve(&mut rng);
+                population
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
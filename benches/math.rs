@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dinai::math::Matrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn mul_matrix(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let small: Matrix<f32, 1, 8> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let layer: Matrix<f32, 8, 16> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let a: Matrix<f32, 64, 64> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let b: Matrix<f32, 64, 64> = Matrix::with_random(-1.0, 1.0, &mut rng);
+
+    c.bench_function("mul_matrix 1x8 * 8x16", |bench| {
+        bench.iter(|| black_box(&small).mul_matrix(black_box(&layer)))
+    });
+    c.bench_function("mul_matrix 64x64 * 64x64", |bench| {
+        bench.iter(|| black_box(&a).mul_matrix(black_box(&b)))
+    });
+    c.bench_function("mul_matrix_f32 64x64 * 64x64", |bench| {
+        bench.iter(|| black_box(&a).mul_matrix_f32(black_box(&b)))
+    });
+}
+
+criterion_group!(benches, mul_matrix);
+criterion_main!(benches);
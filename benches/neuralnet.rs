@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dinai::math::Matrix;
+use dinai::neuralnet::{NeuralNetwork, RecurrentNetwork};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn feed(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let input: Matrix<f32, 1, 8> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let batch: Matrix<f32, 64, 8> = Matrix::with_random(-1.0, 1.0, &mut rng);
+    let nnet: NeuralNetwork<8, 16, 2, 2> = NeuralNetwork::new(&mut rng);
+    let mut rnn: RecurrentNetwork<8, 4, 2> = RecurrentNetwork::new(&mut rng);
+
+    c.bench_function("NeuralNetwork::feed", |bench| {
+        bench.iter(|| nnet.feed(black_box(&input)))
+    });
+    c.bench_function("NeuralNetwork::feed_batch 64", |bench| {
+        bench.iter(|| nnet.feed_batch(black_box(&batch)))
+    });
+    c.bench_function("RecurrentNetwork::feed", |bench| {
+        bench.iter(|| rnn.feed(black_box(&input)))
+    });
+}
+
+criterion_group!(benches, feed);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+//! Saving and restoring the state of a training run.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A snapshot of the best genomes of a generation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use dinai::checkpoint::Checkpoint;
+/// use dinai::neuralnet::NeuralNetwork;
+///
+/// let nnet: NeuralNetwork<3, 4, 1> = NeuralNetwork::new(&mut rand::thread_rng());
+/// let checkpoint = Checkpoint {
+///     generation: 10,
+///     best_score: 42.0,
+///     genomes: vec![nnet],
+/// };
+///
+/// let path = checkpoint.save("checkpoints").unwrap();
+/// let restored: Checkpoint<NeuralNetwork<3, 4, 1>> = Checkpoint::load(path).unwrap();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint<G> {
+    /// Generation in which the genomes were evaluated.
+    pub generation: u32,
+
+    /// Score of the best genome of the generation.
+    pub best_score: f32,
+
+    /// Genomes ordered from the best one.
+    pub genomes: Vec<G>,
+}
+
+impl<G: Serialize> Checkpoint<G> {
+    /// Writes this checkpoint as JSON into the directory `dir`, which is created if needed. The
+    /// file is named after the generation and its path is returned.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> io::Result<PathBuf> {
+        fs::create_dir_all(&dir)?;
+
+        let path = dir
+            .as_ref()
+            .join(format!("generation-{:05}.json", self.generation));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+
+        Ok(path)
+    }
+}
+
+impl<G: DeserializeOwned> Checkpoint<G> {
+    /// Reads a checkpoint previously written by [`save`].
+    ///
+    /// [`save`]: #method.save
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
@@ -0,0 +1,584 @@
+//! Simulation parameters loaded from a TOML file.
+//!
+//! Every value is optional and falls back to its default, so a config file only needs to list
+//! what it changes:
+//!
+//! ```toml
+//! [window]
+//! width = 1920
+//! height = 1080
+//!
+//! [simulation]
+//! population_size = 500
+//! gravity = 900.0
+//!
+//! [obstacle]
+//! max_speed = 1500.0
+//!
+//! [curriculum]
+//! metric = "generation"
+//!
+//! [[curriculum.stages]]
+//! from = 20
+//! speed = 500.0
+//! bird_chance = 0.3
+//!
+//! [keys]
+//! pause = "Space"
+//! ```
+
+use crate::input::{Action, InputMap};
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// All configurable parameters of the simulation.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Window parameters.
+    pub window: WindowSettings,
+
+    /// Physics and population parameters.
+    pub simulation: SimulationSettings,
+
+    /// Obstacle movement parameters.
+    pub obstacle: ObstacleSettings,
+
+    /// Schedule of increasing difficulty.
+    pub curriculum: CurriculumSettings,
+
+    /// Weights of the terms of the fitness function.
+    pub fitness: FitnessSettings,
+
+    /// Parameters of the genetic algorithm.
+    pub genetic: GeneticSettings,
+
+    /// Names of the keys bound to actions, e.g. `"Space"`. Actions that are not listed keep their
+    /// default keys.
+    pub keys: BTreeMap<Action, String>,
+}
+
+/// Window parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowSettings {
+    /// Width of the window, which is also the width of the world.
+    pub width: u32,
+
+    /// Height of the window.
+    pub height: u32,
+
+    /// Whether the window can be resized. The world grows and shrinks with it unless
+    /// `fixed_resolution` is set.
+    pub resizable: bool,
+
+    /// Keeps the world at `width` x `height` pixels and scales it to the size of the window.
+    pub fixed_resolution: bool,
+
+    /// Path of a TrueType font for all text. The bundled font is used if not set.
+    pub font: Option<PathBuf>,
+
+    /// Maximal number of frames per second, unlimited if not set.
+    pub frame_limit: Option<u32>,
+
+    /// Whether presenting a frame waits for the vertical sync of the display.
+    pub vsync: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            resizable: true,
+            fixed_resolution: false,
+            font: None,
+            frame_limit: None,
+            vsync: true,
+        }
+    }
+}
+
+/// Physics and population parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationSettings {
+    /// Number of players in every generation.
+    pub population_size: usize,
+
+    /// Duration of one fixed update in seconds.
+    pub step_s: f32,
+
+    /// Gravity in pixels per second squared.
+    pub gravity: f32,
+
+    /// Vertical velocity of a player right after jumping in pixels per second. Negative values
+    /// point upwards.
+    pub jump_velocity: f32,
+
+    /// Factor of the gravity while a rising player holds the jump.
+    pub jump_hold_gravity: f32,
+
+    /// Maximal time in seconds after jumping during which holding the jump reduces gravity.
+    pub max_jump_hold_s: f32,
+
+    /// Initial simulation speed multiplier of the window, overridden by `--speed`.
+    pub speed: f32,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            population_size: 1000,
+            step_s: 1.0 / 30.0,
+            gravity: 800.0,
+            jump_velocity: -350.0,
+            jump_hold_gravity: 0.5,
+            max_jump_hold_s: 0.3,
+            speed: 1.0,
+        }
+    }
+}
+
+/// Obstacle movement parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObstacleSettings {
+    /// Speed of the obstacle at the start of every generation in pixels per second.
+    pub speed: f32,
+
+    /// Increase of the obstacle speed in pixels per second squared.
+    pub acceleration: f32,
+
+    /// Maximal speed of the obstacle in pixels per second.
+    pub max_speed: f32,
+
+    /// Probability in `[0, 1]` that a spawned obstacle is a bird flying at head height instead of
+    /// a cactus.
+    pub bird_chance: f32,
+
+    /// Distance in pixels behind the right edge of the world at which obstacles spawn. Larger
+    /// gaps spawn obstacles less often.
+    pub spawn_gap: f32,
+}
+
+impl Default for ObstacleSettings {
+    fn default() -> Self {
+        Self {
+            speed: 400.0,
+            acceleration: 30.0,
+            max_speed: 2000.0,
+            bird_chance: 0.3,
+            spawn_gap: 0.0,
+        }
+    }
+}
+
+/// Parameters of the genetic algorithm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneticSettings {
+    /// Probability in `[0, 1]` that a single weight is mutated.
+    pub mutation_rate: f32,
+
+    /// Number of the best players carried over unmutated into the next generation. Must be less
+    /// than `simulation.population_size`, so that at least one child is bred.
+    pub elite_count: usize,
+
+    /// Euclidean distance between the weights of two networks below which they belong to the same
+    /// species.
+    /// Speciation is disabled if not set.
+    pub species_threshold: Option<f32>,
+
+    /// Mutated weights are clamped into `[-weight_clamp, weight_clamp]`. Weights are unbounded if
+    /// not set.
+    pub weight_clamp: Option<f32>,
+
+    /// Fraction in `[0, 1)` by which every weight shrinks towards zero in every generation.
+    pub weight_decay: f32,
+}
+
+impl Default for GeneticSettings {
+    fn default() -> Self {
+        Self {
+            mutation_rate: 0.05,
+            elite_count: 2,
+            species_threshold: None,
+            weight_clamp: None,
+            weight_decay: 0.0,
+        }
+    }
+}
+
+/// Weights of the terms summed into the fitness of a player. By default the fitness is the time
+/// survived.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FitnessSettings {
+    /// Fitness per second survived.
+    pub survival: f32,
+
+    /// Fitness per cleared obstacle.
+    pub obstacles: f32,
+
+    /// Fitness per pixel the world moved past the player.
+    pub distance: f32,
+
+    /// Fitness subtracted per jump.
+    pub jump_penalty: f32,
+
+    /// Fitness per obstacle cleared by less than `close_call_distance` pixels.
+    pub close_call: f32,
+
+    /// Distance in pixels below which clearing an obstacle counts as a close call.
+    pub close_call_distance: f32,
+}
+
+impl Default for FitnessSettings {
+    fn default() -> Self {
+        Self {
+            survival: 1.0,
+            obstacles: 0.0,
+            distance: 0.0,
+            jump_penalty: 0.0,
+            close_call: 0.0,
+            close_call_distance: 10.0,
+        }
+    }
+}
+
+/// What the stages of a curriculum are reached by.
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurriculumMetric {
+    /// Number of the generation.
+    #[default]
+    Generation,
+
+    /// Best score of the previous generation.
+    BestScore,
+}
+
+/// Schedule of increasing difficulty. Without stages the obstacle parameters never change.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurriculumSettings {
+    /// What the stages are reached by.
+    pub metric: CurriculumMetric,
+
+    /// Stages ordered by the value they start from.
+    pub stages: Vec<CurriculumStage>,
+}
+
+/// Obstacle parameters overridden once the curriculum metric reaches `from`. Parameters that are
+/// not set keep the values of the `obstacle` section.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurriculumStage {
+    /// Value of the metric the stage starts from.
+    pub from: f32,
+
+    /// Initial obstacle speed in pixels per second.
+    pub speed: Option<f32>,
+
+    /// Maximal obstacle speed in pixels per second.
+    pub max_speed: Option<f32>,
+
+    /// Probability that a spawned obstacle is a bird.
+    pub bird_chance: Option<f32>,
+
+    /// Distance in pixels behind the right edge of the world at which obstacles spawn.
+    pub spawn_gap: Option<f32>,
+}
+
+impl Config {
+    /// Loads a config from the TOML file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+
+        Self::parse(&content)
+    }
+
+    /// Parses a config from TOML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::config::Config;
+    ///
+    /// let config = Config::parse("[simulation]\npopulation_size = 50").unwrap();
+    ///
+    /// assert_eq!(config.simulation.population_size, 50);
+    /// assert_eq!(config.window.width, 1280);
+    /// ```
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let config: Config = toml::from_str(content).map_err(|e| Error::Config(e.to_string()))?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks that all values are usable by the simulation.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.simulation.population_size < 2 {
+            return Err(Error::Config(
+                "population_size must be at least 2".to_string(),
+            ));
+        }
+
+        if self.genetic.elite_count >= self.simulation.population_size {
+            return Err(Error::Config(
+                "elite_count must be less than population_size".to_string(),
+            ));
+        }
+
+        let values = [
+            ("simulation.step_s", Some(self.simulation.step_s)),
+            ("simulation.gravity", Some(self.simulation.gravity)),
+            (
+                "simulation.jump_velocity",
+                Some(self.simulation.jump_velocity),
+            ),
+            (
+                "simulation.jump_hold_gravity",
+                Some(self.simulation.jump_hold_gravity),
+            ),
+            (
+                "simulation.max_jump_hold_s",
+                Some(self.simulation.max_jump_hold_s),
+            ),
+            ("simulation.speed", Some(self.simulation.speed)),
+            ("obstacle.speed", Some(self.obstacle.speed)),
+            ("obstacle.acceleration", Some(self.obstacle.acceleration)),
+            ("obstacle.max_speed", Some(self.obstacle.max_speed)),
+            ("obstacle.bird_chance", Some(self.obstacle.bird_chance)),
+            ("obstacle.spawn_gap", Some(self.obstacle.spawn_gap)),
+            ("fitness.survival", Some(self.fitness.survival)),
+            ("fitness.obstacles", Some(self.fitness.obstacles)),
+            ("fitness.distance", Some(self.fitness.distance)),
+            ("fitness.jump_penalty", Some(self.fitness.jump_penalty)),
+            ("fitness.close_call", Some(self.fitness.close_call)),
+            (
+                "fitness.close_call_distance",
+                Some(self.fitness.close_call_distance),
+            ),
+            ("genetic.mutation_rate", Some(self.genetic.mutation_rate)),
+            ("genetic.species_threshold", self.genetic.species_threshold),
+            ("genetic.weight_clamp", self.genetic.weight_clamp),
+            ("genetic.weight_decay", Some(self.genetic.weight_decay)),
+        ];
+        let stage_values = self.curriculum.stages.iter().flat_map(|stage| {
+            [
+                ("curriculum.stages.from", Some(stage.from)),
+                ("curriculum.stages.speed", stage.speed),
+                ("curriculum.stages.max_speed", stage.max_speed),
+                ("curriculum.stages.bird_chance", stage.bird_chance),
+                ("curriculum.stages.spawn_gap", stage.spawn_gap),
+            ]
+        });
+        // Infinite or NaN values would turn the fitness of every player into NaN.
+        if let Some((name, _)) = values
+            .iter()
+            .copied()
+            .chain(stage_values)
+            .find(|(_, value)| value.is_some_and(|value| !value.is_finite()))
+        {
+            return Err(Error::Config(format!("{} must be finite", name)));
+        }
+
+        let stage_max_speeds = self
+            .curriculum
+            .stages
+            .iter()
+            .filter_map(|stage| stage.max_speed);
+        if Some(self.obstacle.max_speed)
+            .into_iter()
+            .chain(stage_max_speeds)
+            .any(|max_speed| max_speed <= 0.0)
+        {
+            return Err(Error::Config("max_speed must be positive".to_string()));
+        }
+
+        if self.simulation.step_s <= 0.0 {
+            return Err(Error::Config("step_s must be positive".to_string()));
+        }
+
+        if self.simulation.speed <= 0.0 {
+            return Err(Error::Config("speed must be positive".to_string()));
+        }
+
+        if !(0.0..=1.0).contains(&self.simulation.jump_hold_gravity) {
+            return Err(Error::Config(
+                "jump_hold_gravity must be between 0 and 1".to_string(),
+            ));
+        }
+
+        let stage_chances = self
+            .curriculum
+            .stages
+            .iter()
+            .filter_map(|stage| stage.bird_chance);
+        for bird_chance in Some(self.obstacle.bird_chance)
+            .into_iter()
+            .chain(stage_chances)
+        {
+            if !(0.0..=1.0).contains(&bird_chance) {
+                return Err(Error::Config(
+                    "bird_chance must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+
+        let stages = &self.curriculum.stages;
+        if stages.windows(2).any(|pair| pair[0].from > pair[1].from) {
+            return Err(Error::Config(
+                "curriculum stages must be ordered by from".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.genetic.mutation_rate) {
+            return Err(Error::Config(
+                "mutation_rate must be between 0 and 1".to_string(),
+            ));
+        }
+
+        if self.genetic.weight_clamp.is_some_and(|clamp| clamp <= 0.0) {
+            return Err(Error::Config("weight_clamp must be positive".to_string()));
+        }
+
+        if !(0.0..1.0).contains(&self.genetic.weight_decay) {
+            return Err(Error::Config(
+                "weight_decay must be at least 0 and less than 1".to_string(),
+            ));
+        }
+
+        if self.window.width == 0 || self.window.height == 0 {
+            return Err(Error::Config("window size must be positive".to_string()));
+        }
+
+        InputMap::from_names(&self.keys)?;
+
+        Ok(())
+    }
+}
+
+/// Reloads a config file whenever it is modified, so that a long training can be tuned without
+/// restarting it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use dinai::config::ConfigWatcher;
+///
+/// let mut watcher = ConfigWatcher::new("dinai.toml");
+///
+/// // Later, e.g. between two generations:
+/// if let Some(Ok(config)) = watcher.poll() {
+///     println!("new mutation rate: {}", config.genetic.mutation_rate);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher of the config file at `path`. The file as it is now counts as loaded.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let modified = modified_time(&path);
+
+        Self { path, modified }
+    }
+
+    /// Returns the path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the config if the file was modified since it was last loaded, and returns `None` if
+    /// it was not. A file that cannot be loaded is not retried until it is modified again.
+    pub fn poll(&mut self) -> Option<Result<Config, Error>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        Some(Config::load(&self.path))
+    }
+}
+
+/// Returns the time the file at `path` was last modified, or `None` if it cannot be read.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_is_default() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_partial_section() {
+        let config = Config::parse("[obstacle]\nmax_speed = 1500.0").unwrap();
+
+        assert_eq!(config.obstacle.max_speed, 1500.0);
+        assert_eq!(config.obstacle.speed, ObstacleSettings::default().speed);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Config::parse("[simulation]\npopulation_size = 1").is_err());
+        assert!(Config::parse("[simulation]\nunknown = 1").is_err());
+        assert!(Config::parse("[window]\nwidth = \"wide\"").is_err());
+        assert!(Config::parse("[obstacle]\nbird_chance = 1.5").is_err());
+        assert!(Config::parse("[simulation]\njump_hold_gravity = -1.0").is_err());
+        assert!(Config::parse("[genetic]\nweight_clamp = 0.0").is_err());
+        assert!(Config::parse("[genetic]\nweight_decay = 1.0").is_err());
+        assert!(Config::parse("[genetic]\nmutation_rate = 1.5").is_err());
+        assert!(Config::parse("[genetic]\nelite_count = 1000").is_err());
+        assert!(Config::parse("[simulation]\nspeed = 0.0").is_err());
+        assert!(Config::parse("[obstacle]\nmax_speed = 0.0").is_err());
+        assert!(Config::parse("[[curriculum.stages]]\nmax_speed = -10.0").is_err());
+        assert!(Config::parse("[fitness]\ndistance = inf").is_err());
+        assert!(Config::parse("[simulation]\nstep_s = nan").is_err());
+        assert!(Config::parse("[[curriculum.stages]]\nspeed = -inf").is_err());
+        assert!(Config::parse("[keys]\njump = \"Space\"").is_err());
+        assert!(Config::parse("[keys]\npause = \"NoSuchKey\"").is_err());
+        assert!(
+            Config::parse("[[curriculum.stages]]\nfrom = 10\n[[curriculum.stages]]\nfrom = 5")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_watcher() {
+        let path = std::env::temp_dir().join(format!("dinai-watcher-{}.toml", std::process::id()));
+        fs::write(&path, "[genetic]\nmutation_rate = 0.1").unwrap();
+        let mut watcher = ConfigWatcher::new(&path);
+
+        assert!(watcher.poll().is_none());
+
+        fs::write(&path, "[genetic]\nmutation_rate = 0.2").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(10))
+            .unwrap();
+
+        let config = watcher.poll().unwrap().unwrap();
+        assert_eq!(config.genetic.mutation_rate, 0.2);
+        assert!(watcher.poll().is_none());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! A developer console running typed commands, e.g. `set mutation_rate 0.1`.
+//!
+//! A game registers a handler for every command it understands. The console splits a typed line
+//! into the name of the command and its arguments and calls the handler of that name with the
+//! game, so that commands can change anything the game owns:
+//!
+//! ```
+//! use dinai::console::Console;
+//! use dinai::Error;
+//!
+//! struct Game {
+//!     speed: f32,
+//! }
+//!
+//! let mut console = Console::new();
+//! console.register("speed", "speed <x>", |game: &mut Game, args| {
+//!     let speed = args
+//!         .first()
+//!         .and_then(|arg| arg.parse().ok())
+//!         .ok_or_else(|| Error::Config("expected a number".to_string()))?;
+//!     game.speed = speed;
+//!     Ok(format!("speed is {}", speed))
+//! });
+//!
+//! let mut game = Game { speed: 1.0 };
+//! console.execute(&mut game, "speed 2.5");
+//!
+//! assert_eq!(game.speed, 2.5);
+//! assert_eq!(console.lines().last().unwrap(), "speed is 2.5");
+//! ```
+
+use crate::Error;
+use std::collections::BTreeMap;
+
+/// Runs a command with its arguments on the game, returning what is printed into the console.
+pub type Handler<T> = fn(&mut T, &[&str]) -> Result<String, Error>;
+
+/// Maximal number of lines kept in the console.
+const MAX_LINES: usize = 100;
+
+struct Command<T> {
+    usage: &'static str,
+    handler: Handler<T>,
+}
+
+/// Commands that can be run on a game of type `T` and the lines printed by them.
+pub struct Console<T> {
+    commands: BTreeMap<&'static str, Command<T>>,
+    lines: Vec<String>,
+    open: bool,
+}
+
+impl<T> Default for Console<T> {
+    fn default() -> Self {
+        Self {
+            commands: BTreeMap::new(),
+            lines: Vec::new(),
+            open: false,
+        }
+    }
+}
+
+impl<T> Console<T> {
+    /// Creates a closed console that only knows the `help` command.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` as the command `name`, replacing a previous one of the same name.
+    /// `usage` is shown by `help`, e.g. `"save <path>"`.
+    pub fn register(&mut self, name: &'static str, usage: &'static str, handler: Handler<T>) {
+        self.commands.insert(name, Command { usage, handler });
+    }
+
+    /// Runs the command typed as `line` on `target` and prints the line and its result. The
+    /// command `help` lists the usage of all commands.
+    pub fn execute(&mut self, target: &mut T, line: &str) {
+        let mut words = line.split_whitespace();
+        let name = match words.next() {
+            Some(name) => name,
+            None => return,
+        };
+        let args: Vec<&str> = words.collect();
+        self.print(format!("> {}", line.trim()));
+
+        let output = match self.commands.get(name) {
+            Some(command) => (command.handler)(target, &args).unwrap_or_else(|err| err.to_string()),
+            None if name == "help" => self.usage(),
+            None => format!("unknown command {}, try help", name),
+        };
+        for line in output.lines() {
+            self.print(line.to_string());
+        }
+    }
+
+    /// Returns the usage of all commands, one per line.
+    fn usage(&self) -> String {
+        let usages: Vec<&str> = self
+            .commands
+            .values()
+            .map(|command| command.usage)
+            .collect();
+        format!("help\n{}", usages.join("\n"))
+    }
+
+    /// Appends `line` to the output, dropping the oldest line once there are too many.
+    fn print(&mut self, line: String) {
+        if self.lines.len() == MAX_LINES {
+            self.lines.remove(0);
+        }
+        self.lines.push(line);
+    }
+
+    /// Returns the lines printed so far, from the oldest one.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Returns true while the console is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Shows or hides the console.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(total: &mut i32, args: &[&str]) -> Result<String, Error> {
+        for arg in args {
+            *total += arg
+                .parse::<i32>()
+                .map_err(|e| Error::Config(e.to_string()))?;
+        }
+
+        Ok(format!("total {}", total))
+    }
+
+    #[test]
+    fn test_execute() {
+        let mut console = Console::new();
+        console.register("add", "add <n>...", add);
+        let mut total = 0;
+
+        console.execute(&mut total, "  add 1   2 ");
+        console.execute(&mut total, "");
+
+        assert_eq!(total, 3);
+        assert_eq!(console.lines(), ["> add 1   2", "total 3"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut console = Console::new();
+        console.register("add", "add <n>...", add);
+        let mut total = 0;
+
+        console.execute(&mut total, "add x");
+        console.execute(&mut total, "sub 1");
+        console.execute(&mut total, "help");
+
+        assert_eq!(total, 0);
+        assert!(console.lines()[1].starts_with("config error"));
+        assert_eq!(console.lines()[3], "unknown command sub, try help");
+        assert_eq!(&console.lines()[5..], ["help", "add <n>..."]);
+    }
+}
@@ -0,0 +1,145 @@
+//! Difficulty that increases as the players get better.
+
+use crate::config::{CurriculumMetric, CurriculumSettings, ObstacleSettings};
+
+/// Decides the obstacle parameters of every generation according to a schedule of stages.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::config::{CurriculumSettings, CurriculumStage, ObstacleSettings};
+/// use dinai::curriculum::Curriculum;
+///
+/// let settings = CurriculumSettings {
+///     stages: vec![CurriculumStage {
+///         from: 10.0,
+///         speed: Some(600.0),
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+/// let curriculum = Curriculum::new(ObstacleSettings::default(), settings);
+///
+/// assert_eq!(curriculum.obstacle_settings(5, 0.0).speed, 400.0);
+/// assert_eq!(curriculum.obstacle_settings(10, 0.0).speed, 600.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Curriculum {
+    base: ObstacleSettings,
+    settings: CurriculumSettings,
+}
+
+impl Curriculum {
+    /// Creates a curriculum whose stages override `base`.
+    pub fn new(base: ObstacleSettings, settings: CurriculumSettings) -> Self {
+        Self { base, settings }
+    }
+
+    /// Returns the index of the stage reached by a generation with the given best score of the
+    /// previous generation, or `None` before the first stage.
+    pub fn stage(&self, generation: u32, best_score: f32) -> Option<usize> {
+        let value = match self.settings.metric {
+            CurriculumMetric::Generation => generation as f32,
+            CurriculumMetric::BestScore => best_score,
+        };
+
+        self.settings
+            .stages
+            .iter()
+            .rposition(|stage| stage.from <= value)
+    }
+
+    /// Returns the obstacle parameters of a generation with the given best score of the previous
+    /// generation. Every stage up to the reached one is applied in order, so a stage only needs
+    /// to list what it changes.
+    pub fn obstacle_settings(&self, generation: u32, best_score: f32) -> ObstacleSettings {
+        let mut settings = self.base.clone();
+        let reached = match self.stage(generation, best_score) {
+            Some(stage) => stage + 1,
+            None => 0,
+        };
+
+        for stage in &self.settings.stages[..reached] {
+            if let Some(speed) = stage.speed {
+                settings.speed = speed;
+            }
+            if let Some(max_speed) = stage.max_speed {
+                settings.max_speed = max_speed;
+            }
+            if let Some(bird_chance) = stage.bird_chance {
+                settings.bird_chance = bird_chance;
+            }
+            if let Some(spawn_gap) = stage.spawn_gap {
+                settings.spawn_gap = spawn_gap;
+            }
+        }
+
+        settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CurriculumStage;
+
+    fn curriculum(metric: CurriculumMetric) -> Curriculum {
+        let settings = CurriculumSettings {
+            metric,
+            stages: vec![
+                CurriculumStage {
+                    from: 0.0,
+                    bird_chance: Some(0.0),
+                    ..Default::default()
+                },
+                CurriculumStage {
+                    from: 20.0,
+                    speed: Some(500.0),
+                    ..Default::default()
+                },
+                CurriculumStage {
+                    from: 50.0,
+                    bird_chance: Some(0.5),
+                    spawn_gap: Some(100.0),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        Curriculum::new(ObstacleSettings::default(), settings)
+    }
+
+    #[test]
+    fn test_stages_accumulate() {
+        let curriculum = curriculum(CurriculumMetric::Generation);
+
+        let settings = curriculum.obstacle_settings(0, 0.0);
+        assert_eq!(settings.bird_chance, 0.0);
+        assert_eq!(settings.speed, 400.0);
+
+        let settings = curriculum.obstacle_settings(60, 0.0);
+        assert_eq!(settings.speed, 500.0);
+        assert_eq!(settings.bird_chance, 0.5);
+        assert_eq!(settings.spawn_gap, 100.0);
+    }
+
+    #[test]
+    fn test_best_score_metric() {
+        let curriculum = curriculum(CurriculumMetric::BestScore);
+
+        assert_eq!(curriculum.stage(100, 10.0), Some(0));
+        assert_eq!(curriculum.stage(0, 20.0), Some(1));
+    }
+
+    #[test]
+    fn test_before_first_stage() {
+        let mut curriculum = curriculum(CurriculumMetric::Generation);
+        curriculum.settings.stages[0].from = 5.0;
+
+        assert_eq!(curriculum.stage(2, 0.0), None);
+        assert_eq!(
+            curriculum.obstacle_settings(2, 0.0),
+            ObstacleSettings::default()
+        );
+    }
+}
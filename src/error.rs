@@ -0,0 +1,87 @@
+//! The error type shared by the whole crate.
+
+use sdl2::render::TextureValueError;
+use sdl2::ttf::{FontError, InitError};
+use sdl2::video::WindowBuildError;
+use sdl2::IntegerOrSdlError;
+use std::fmt;
+use std::io;
+
+/// An error that can occur in this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// An error reported by `SDL2`.
+    Sdl(String),
+
+    /// An error reported by `SDL2_ttf`.
+    Ttf(String),
+
+    /// An I/O error.
+    Io(io::Error),
+
+    /// An invalid configuration.
+    Config(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Sdl(msg) => write!(f, "SDL error: {}", msg),
+            Error::Ttf(msg) => write!(f, "TTF error: {}", msg),
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Config(msg) => write!(f, "config error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Most `SDL2` calls report failures as plain strings.
+impl From<String> for Error {
+    fn from(msg: String) -> Self {
+        Error::Sdl(msg)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<WindowBuildError> for Error {
+    fn from(err: WindowBuildError) -> Self {
+        Error::Sdl(err.to_string())
+    }
+}
+
+impl From<IntegerOrSdlError> for Error {
+    fn from(err: IntegerOrSdlError) -> Self {
+        Error::Sdl(err.to_string())
+    }
+}
+
+impl From<TextureValueError> for Error {
+    fn from(err: TextureValueError) -> Self {
+        Error::Sdl(err.to_string())
+    }
+}
+
+impl From<FontError> for Error {
+    fn from(err: FontError) -> Self {
+        Error::Ttf(err.to_string())
+    }
+}
+
+impl From<InitError> for Error {
+    fn from(err: InitError) -> Self {
+        Error::Ttf(err.to_string())
+    }
+}
@@ -0,0 +1,118 @@
+//! Fitness of players computed from what they achieved.
+
+use crate::config::FitnessSettings;
+
+/// What a player achieved during its life.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Achievements {
+    /// Time survived in seconds.
+    pub survival_s: f32,
+
+    /// Number of obstacles that passed the player.
+    pub obstacles_cleared: u32,
+
+    /// Distance the world moved past the player in pixels.
+    pub distance: f32,
+
+    /// Number of jumps.
+    pub jumps: u32,
+
+    /// Number of cleared obstacles that missed the player by only a few pixels.
+    pub close_calls: u32,
+}
+
+/// Computes the fitness of a player from its achievements.
+///
+/// Any closure taking `&Achievements` is a fitness function:
+///
+/// ```
+/// use dinai::fitness::{Achievements, FitnessFn};
+///
+/// let fitness = |a: &Achievements| a.obstacles_cleared as f32;
+/// let achievements = Achievements {
+///     obstacles_cleared: 3,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(fitness.fitness(&achievements), 3.0);
+/// ```
+pub trait FitnessFn {
+    /// Returns the fitness of a player with the given achievements. Higher is better.
+    fn fitness(&self, achievements: &Achievements) -> f32;
+}
+
+impl<F: Fn(&Achievements) -> f32> FitnessFn for F {
+    fn fitness(&self, achievements: &Achievements) -> f32 {
+        self(achievements)
+    }
+}
+
+/// Weighted sum of all achievements, with the weights taken from the config.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::config::FitnessSettings;
+/// use dinai::fitness::{Achievements, FitnessFn, WeightedFitness};
+///
+/// let fitness = WeightedFitness::new(FitnessSettings {
+///     obstacles: 2.0,
+///     jump_penalty: 0.5,
+///     ..Default::default()
+/// });
+/// let achievements = Achievements {
+///     survival_s: 10.0,
+///     obstacles_cleared: 3,
+///     jumps: 4,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(fitness.fitness(&achievements), 14.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedFitness {
+    weights: FitnessSettings,
+}
+
+impl WeightedFitness {
+    /// Creates a fitness function with the given weights.
+    pub fn new(weights: FitnessSettings) -> Self {
+        Self { weights }
+    }
+
+    /// Returns the weights of the achievements.
+    pub fn weights(&self) -> &FitnessSettings {
+        &self.weights
+    }
+}
+
+impl FitnessFn for WeightedFitness {
+    fn fitness(&self, achievements: &Achievements) -> f32 {
+        let w = &self.weights;
+
+        w.survival * achievements.survival_s
+            + w.obstacles * achievements.obstacles_cleared as f32
+            + w.distance * achievements.distance
+            + w.close_call * achievements.close_calls as f32
+            - w.jump_penalty * achievements.jumps as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_survival_time() {
+        let fitness = WeightedFitness::new(FitnessSettings::default());
+        let achievements = Achievements {
+            survival_s: 12.5,
+            obstacles_cleared: 4,
+            distance: 5000.0,
+            jumps: 6,
+            close_calls: 2,
+        };
+
+        assert_eq!(fitness.fitness(&achievements), 12.5);
+    }
+}
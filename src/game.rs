@@ -0,0 +1,356 @@
+//! A fixed timestep game loop running a [`Game`] in a window.
+//!
+//! The simulation advances in fixed updates of `step_s` seconds, as many as fit into the time
+//! passed since the previous frame multiplied by the speed. What is left over is passed to
+//! [`Game::draw`] as the interpolation, so that movement stays smooth at any frame rate.
+//!
+//! [`Game`]: trait.Game.html
+//! [`Game::draw`]: trait.Game.html#tymethod.draw
+
+use crate::config::Config;
+use crate::input::InputMap;
+use crate::profiler::{Phase, ProfileRecorder, Profiler};
+#[cfg(feature = "recorder")]
+use crate::recorder::Recorder;
+use crate::window::{
+    FontSource, GameWindow, RateCounter, TextRenderer, WindowConfig, DEFAULT_POINT_SIZE,
+};
+use crate::Error;
+use log::info;
+use std::fs::File;
+use std::io::BufWriter;
+#[cfg(feature = "recorder")]
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Recordings play at this frame rate.
+#[cfg(feature = "recorder")]
+const RECORD_FPS: u32 = 30;
+
+// While fast-forwarding, events are polled at least this often to keep the window responsive.
+const FAST_FORWARD_BUDGET: Duration = Duration::from_millis(100);
+
+// Longest time in seconds simulated in one frame, so that the simulation does not spiral after a
+// stall.
+const MAX_FRAME_S: f32 = 0.3;
+
+/// Whether the fixed updates of the simulation run.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SimulationState {
+    /// Updates run at the current speed.
+    Running,
+
+    /// No updates run, the last frame is drawn again.
+    Paused,
+
+    /// Runs exactly one fixed update and pauses again.
+    Stepping,
+
+    /// Runs as fast as possible without drawing until the target is reached.
+    FastForward(FastForward),
+}
+
+/// Where fast-forwarding stops.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FastForward {
+    /// Round at which fast-forwarding stops.
+    pub until_generation: u32,
+
+    /// Best score at which fast-forwarding stops, if any.
+    pub target_score: Option<f32>,
+}
+
+impl FastForward {
+    /// Returns true if a game with the given progress reached the target.
+    pub fn is_reached(&self, generation: u32, best_score: f32) -> bool {
+        generation >= self.until_generation
+            || self.target_score.is_some_and(|score| best_score >= score)
+    }
+}
+
+/// Everything a [`Game`] can access while it runs.
+///
+/// [`Game`]: trait.Game.html
+pub struct Context<'a> {
+    /// The window the game runs in.
+    pub game_window: &'a mut GameWindow,
+
+    /// Renderer of all text.
+    pub text_renderer: &'a TextRenderer<'a>,
+
+    /// Duration of one fixed update in seconds.
+    pub step_s: f32,
+
+    /// Multiplier of the simulated time, at least 0.1.
+    pub speed: f32,
+
+    /// Whether the fixed updates run.
+    pub state: SimulationState,
+
+    /// Actions bound to keys and which of them are pressed.
+    pub input: InputMap,
+
+    /// Number of fixed updates per second.
+    pub ups: RateCounter,
+
+    /// Time spent in the phases of recent frames.
+    pub profiler: Profiler,
+
+    /// Whether debug information is drawn.
+    pub show_debug: bool,
+}
+
+/// A game driven by a [`GameLoop`].
+///
+/// [`GameLoop`]: struct.GameLoop.html
+pub trait Game {
+    /// Draws the current state, `interpolation` seconds of simulated time after the last update.
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error>;
+
+    /// Handles the input polled at the start of the frame.
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<(), Error>;
+
+    /// Advances the game by one fixed update of `ctx.step_s` seconds.
+    fn update(&mut self, ctx: &mut Context) -> Result<(), Error>;
+
+    /// Called after the window has been resized.
+    fn on_resize(&mut self, _ctx: &mut Context) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Returns the number of finished rounds and the best score reached so far, which decide when
+    /// fast-forwarding stops.
+    fn progress(&self) -> (u32, f32);
+}
+
+/// Opens a window and runs a [`Game`] in it with fixed updates.
+///
+/// # Examples
+///
+/// ```no_run
+/// use dinai::config::Config;
+/// use dinai::game::{Context, Game, GameLoop};
+/// use dinai::Error;
+///
+/// struct Counter(u32);
+///
+/// impl Game for Counter {
+///     fn draw(&mut self, ctx: &mut Context, _interpolation: f32) -> Result<(), Error> {
+///         let canvas = ctx.game_window.canvas_mut();
+///         ctx.text_renderer.draw_text(&self.0.to_string(), 10, 10, 0.2, canvas)
+///     }
+///
+///     fn handle_input(&mut self, _ctx: &mut Context) -> Result<(), Error> {
+///         Ok(())
+///     }
+///
+///     fn update(&mut self, _ctx: &mut Context) -> Result<(), Error> {
+///         self.0 += 1;
+///         Ok(())
+///     }
+///
+///     fn progress(&self) -> (u32, f32) {
+///         (0, self.0 as f32)
+///     }
+/// }
+///
+/// let mut game_loop = GameLoop::new("counter", &Config::default());
+/// game_loop.set_speed(2.0);
+/// game_loop.run(&mut Counter(0)).unwrap();
+/// ```
+pub struct GameLoop {
+    title: &'static str,
+    config: Config,
+    speed: f32,
+    state: SimulationState,
+    profile: Option<ProfileRecorder<BufWriter<File>>>,
+    #[cfg(feature = "recorder")]
+    record: Option<(PathBuf, u32)>,
+}
+
+impl GameLoop {
+    /// Creates a loop opening a window titled `title` with the window and step parameters of
+    /// `config`.
+    pub fn new(title: &'static str, config: &Config) -> Self {
+        Self {
+            title,
+            config: config.clone(),
+            speed: 1.0,
+            state: SimulationState::Running,
+            profile: None,
+            #[cfg(feature = "recorder")]
+            record: None,
+        }
+    }
+
+    /// Sets the initial multiplier of the simulated time. Values below 0.1 are raised to it.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.1);
+    }
+
+    /// Sets the state the simulation starts in, e.g. fast-forwarding.
+    pub fn set_state(&mut self, state: SimulationState) {
+        self.state = state;
+    }
+
+    /// Writes the time spent in every phase of each frame into `recorder`.
+    pub fn set_profile(&mut self, recorder: ProfileRecorder<BufWriter<File>>) {
+        self.profile = Some(recorder);
+    }
+
+    /// Records every `interval`-th frame into a video at `path`.
+    ///
+    /// This method is only available with the `recorder` feature.
+    #[cfg(feature = "recorder")]
+    pub fn set_record<P: AsRef<Path>>(&mut self, path: P, interval: u32) {
+        self.record = Some((path.as_ref().to_path_buf(), interval));
+    }
+
+    /// Opens the window and runs `the_game` in it until the window is closed.
+    pub fn run<G: Game>(&mut self, the_game: &mut G) -> Result<(), Error> {
+        let config = &self.config;
+        let win_conf = WindowConfig {
+            title: self.title,
+            width: config.window.width,
+            height: config.window.height,
+            resizable: config.window.resizable,
+            vsync: config.window.vsync,
+        };
+
+        let mut game_window = GameWindow::new(win_conf)?;
+        game_window.set_frame_limit(config.window.frame_limit);
+        if config.window.fixed_resolution {
+            game_window.set_logical_size(config.window.width, config.window.height)?;
+        }
+
+        let ttf_context = sdl2::ttf::init()?;
+        let font = match &config.window.font {
+            Some(path) => FontSource::Path(path),
+            None => FontSource::default(),
+        };
+        let text_renderer = TextRenderer::with_font(&ttf_context, font, DEFAULT_POINT_SIZE)?;
+
+        let mut ctx = Context {
+            game_window: &mut game_window,
+            text_renderer: &text_renderer,
+            step_s: config.simulation.step_s,
+            speed: self.speed,
+            state: self.state,
+            input: InputMap::from_names(&config.keys)?,
+            ups: RateCounter::new(),
+            profiler: Profiler::new(),
+            show_debug: false,
+        };
+
+        #[cfg(feature = "recorder")]
+        let mut recorder = match &self.record {
+            Some((path, interval)) => {
+                let (width, height) = ctx.game_window.canvas().output_size()?;
+                Some(Recorder::start(path, width, height, RECORD_FPS, *interval)?)
+            }
+            None => None,
+        };
+
+        let mut start_time = Instant::now();
+        let mut lag = 0.0;
+
+        while !ctx.game_window.should_close() {
+            let delta_time = start_time.elapsed().as_secs_f32() * ctx.speed;
+            start_time = Instant::now();
+            lag += delta_time.min(MAX_FRAME_S);
+
+            ctx.game_window.poll();
+            ctx.input.update(ctx.game_window);
+            if ctx.game_window.was_resized() {
+                the_game.on_resize(&mut ctx)?;
+            }
+            the_game.handle_input(&mut ctx)?;
+            if ctx.game_window.should_close() {
+                break;
+            }
+
+            match ctx.state {
+                SimulationState::Running => {
+                    while lag > ctx.step_s {
+                        run_update(the_game, &mut ctx)?;
+                        lag -= ctx.step_s;
+                    }
+                }
+                SimulationState::Paused => lag = 0.0,
+                SimulationState::Stepping => {
+                    run_update(the_game, &mut ctx)?;
+                    ctx.state = SimulationState::Paused;
+                    lag = 0.0;
+                }
+                SimulationState::FastForward(target) => {
+                    let budget_start = Instant::now();
+                    while budget_start.elapsed() < FAST_FORWARD_BUDGET {
+                        run_update(the_game, &mut ctx)?;
+
+                        let (generation, best_score) = the_game.progress();
+                        if target.is_reached(generation, best_score) {
+                            info!(
+                                "Fast-forwarded to generation {}: best score {:.2}",
+                                generation, best_score
+                            );
+                            ctx.state = SimulationState::Running;
+                            break;
+                        }
+                    }
+                    lag = 0.0;
+
+                    // Nothing is drawn until fast-forwarding is done.
+                    end_frame(&mut ctx.profiler, self.profile.as_mut())?;
+                    continue;
+                }
+            }
+
+            let draw_start = Instant::now();
+            the_game.draw(&mut ctx, lag)?;
+            ctx.profiler.add(Phase::Draw, draw_start.elapsed());
+
+            #[cfg(feature = "recorder")]
+            if let Some(recorder) = &mut recorder {
+                recorder.capture(ctx.game_window.canvas())?;
+            }
+
+            ctx.game_window.present();
+            end_frame(&mut ctx.profiler, self.profile.as_mut())?;
+        }
+
+        #[cfg(feature = "recorder")]
+        if let Some(recorder) = recorder {
+            recorder.finish()?;
+        }
+
+        if let Some(profile) = &mut self.profile {
+            profile.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finishes the frame measured by `profiler` and writes its times into
+/// `recorder`.
+fn end_frame(
+    profiler: &mut Profiler,
+    recorder: Option<&mut ProfileRecorder<BufWriter<File>>>,
+) -> Result<(), Error> {
+    let frame = profiler.end_frame();
+    if let Some(recorder) = recorder {
+        recorder.record(&frame)?;
+    }
+
+    Ok(())
+}
+
+/// Runs one fixed update of `the_game` and counts it in the profile.
+fn run_update<G: Game>(the_game: &mut G, ctx: &mut Context) -> Result<(), Error> {
+    let start = Instant::now();
+    the_game.update(ctx)?;
+    ctx.profiler.add(Phase::Update, start.elapsed());
+    ctx.ups.tick();
+
+    Ok(())
+}
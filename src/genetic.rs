@@ -0,0 +1,550 @@
+//! A generic genetic algorithm.
+//!
+//! Anything implementing [`Genome`] can be evolved by a [`Population`]. The simulation is
+//! responsible for evaluating the fitness of every genome between two calls to
+//! [`Population::evolve`].
+//!
+//! [`Genome`]: trait.Genome.html
+//! [`Population`]: struct.Population.html
+//! [`Population::evolve`]: struct.Population.html#method.evolve
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// An individual that can be bred by the genetic algorithm.
+pub trait Genome: Clone {
+    /// Returns how well this individual performed, higher is better.
+    fn fitness(&self) -> f32;
+
+    /// Combines this individual with `other` producing a child.
+    fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self;
+
+    /// Randomly alters this individual as described by `config`.
+    fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R);
+
+    /// Returns how different this individual is from `other`, used to group similar individuals
+    /// into species. All individuals are equal by default.
+    fn distance(&self, _other: &Self) -> f32 {
+        0.0
+    }
+}
+
+/// Parameters of Gaussian weight mutation.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::genetic::MutationConfig;
+///
+/// let config = MutationConfig {
+///     rate: 0.1,
+///     decay: 0.5,
+///     ..Default::default()
+/// };
+///
+/// assert!((config.at_generation(2).rate - 0.025).abs() < 0.00001);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MutationConfig {
+    /// Probability that a single weight is mutated.
+    pub rate: f32,
+
+    /// Standard deviation of the Gaussian value added to a mutated weight.
+    pub sigma: f32,
+
+    /// Mutated weights are clamped into `[-clamp, clamp]`. Weights are unbounded if not set.
+    pub clamp: Option<f32>,
+
+    /// Fraction by which every weight shrinks towards zero before mutation, keeping weights small
+    /// unless larger ones pay off. `0.0` disables the decay.
+    pub weight_decay: f32,
+
+    /// Both `rate` and `sigma` are multiplied by this factor every generation. `1.0` disables
+    /// annealing.
+    pub decay: f32,
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        Self {
+            rate: 0.05,
+            sigma: 0.2,
+            clamp: None,
+            weight_decay: 0.0,
+            decay: 1.0,
+        }
+    }
+}
+
+impl MutationConfig {
+    /// Returns this config annealed for the given generation.
+    pub fn at_generation(&self, generation: u32) -> Self {
+        let factor = self.decay.powi(generation as i32);
+
+        Self {
+            rate: self.rate * factor,
+            sigma: self.sigma * factor,
+            ..*self
+        }
+    }
+}
+
+/// The way parents are chosen when breeding a new generation.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum SelectionStrategy {
+    /// Always breeds the two fittest genomes.
+    #[default]
+    Elitism,
+
+    /// Picks the fittest of the given number of randomly chosen genomes.
+    Tournament(usize),
+
+    /// Picks a genome with probability proportional to its fitness. Negative fitness is treated
+    /// as zero.
+    Roulette,
+
+    /// Picks a genome with probability proportional to its rank, the fittest one having the
+    /// highest rank.
+    Rank,
+}
+
+impl SelectionStrategy {
+    /// Selects a parent from `sorted`, which must be ordered from the fittest genome, and returns
+    /// its index. `nth` is the index of the parent being chosen for the current child, elitism
+    /// uses it to return the `nth` fittest genome.
+    fn select<G: Genome, R: Rng + ?Sized>(&self, sorted: &[G], nth: usize, rng: &mut R) -> usize {
+        let len = sorted.len();
+
+        match *self {
+            SelectionStrategy::Elitism => nth.min(len - 1),
+            SelectionStrategy::Tournament(size) => (0..size.max(1))
+                .map(|_| rng.gen_range(0, len))
+                .min()
+                .unwrap(),
+            SelectionStrategy::Roulette => {
+                let total: f32 = sorted.iter().map(|g| g.fitness().max(0.0)).sum();
+                if total <= 0.0 {
+                    return rng.gen_range(0, len);
+                }
+
+                let mut pick = rng.gen_range(0.0, total);
+                for (i, genome) in sorted.iter().enumerate() {
+                    pick -= genome.fitness().max(0.0);
+                    if pick < 0.0 {
+                        return i;
+                    }
+                }
+
+                len - 1
+            }
+            SelectionStrategy::Rank => {
+                // The genome at index `i` has rank `len - i`.
+                let total = len * (len + 1) / 2;
+                let mut pick = rng.gen_range(0, total);
+                for i in 0..len {
+                    let rank = len - i;
+                    if pick < rank {
+                        return i;
+                    }
+                    pick -= rank;
+                }
+
+                len - 1
+            }
+        }
+    }
+}
+
+/// A population of genomes evolved generation by generation.
+#[derive(Debug, Clone)]
+pub struct Population<G> {
+    genomes: Vec<G>,
+    size: usize,
+    generation: u32,
+    selection: SelectionStrategy,
+    elite_count: usize,
+    mutation: MutationConfig,
+    species_threshold: Option<f32>,
+    species_count: usize,
+}
+
+impl<G: Genome> Population<G> {
+    /// Creates the first generation of a population from the given genomes.
+    pub fn new(genomes: Vec<G>) -> Self {
+        Self {
+            size: genomes.len(),
+            genomes,
+            generation: 0,
+            selection: SelectionStrategy::default(),
+            elite_count: 0,
+            mutation: MutationConfig::default(),
+            species_threshold: None,
+            species_count: 1,
+        }
+    }
+
+    /// Returns the strategy used to choose parents.
+    pub fn selection(&self) -> SelectionStrategy {
+        self.selection
+    }
+
+    /// Sets the strategy used to choose parents.
+    pub fn set_selection(&mut self, selection: SelectionStrategy) {
+        self.selection = selection;
+    }
+
+    /// Returns the mutation parameters of the first generation.
+    pub fn mutation(&self) -> &MutationConfig {
+        &self.mutation
+    }
+
+    /// Sets the mutation parameters of the first generation, later generations use them annealed.
+    pub fn set_mutation(&mut self, mutation: MutationConfig) {
+        self.mutation = mutation;
+    }
+
+    /// Returns the number of fittest genomes copied unchanged into the next generation.
+    pub fn elite_count(&self) -> usize {
+        self.elite_count
+    }
+
+    /// Sets the number of fittest genomes copied unchanged into the next generation.
+    pub fn set_elite_count(&mut self, elite_count: usize) {
+        self.elite_count = elite_count;
+    }
+
+    /// Returns the distance below which genomes belong to the same species, `None` if speciation
+    /// is disabled.
+    pub fn species_threshold(&self) -> Option<f32> {
+        self.species_threshold
+    }
+
+    /// Enables speciation with the given distance threshold or disables it with `None`.
+    ///
+    /// With speciation, genomes are grouped into species by their [`distance`]. Every species
+    /// breeds its own children and the number of children is proportional to the mean fitness of
+    /// the species, so that a single successful species cannot take over the whole population
+    /// at once.
+    ///
+    /// [`distance`]: trait.Genome.html#method.distance
+    pub fn set_species_threshold(&mut self, threshold: Option<f32>) {
+        self.species_threshold = threshold;
+    }
+
+    /// Returns the number of species found by the last [`evolve`], which is always one without
+    /// speciation.
+    ///
+    /// [`evolve`]: #method.evolve
+    pub fn species_count(&self) -> usize {
+        self.species_count
+    }
+
+    /// Returns the number of genomes bred by [`evolve`].
+    ///
+    /// [`evolve`]: #method.evolve
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Sets the number of genomes bred by [`evolve`], so that the population grows or shrinks
+    /// with the next generation. The current generation keeps its size.
+    ///
+    /// [`evolve`]: #method.evolve
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size;
+    }
+
+    /// Returns the genomes of the current generation.
+    pub fn genomes(&self) -> &[G] {
+        &self.genomes
+    }
+
+    /// Returns the genomes of the current generation mutably, so that the simulation can evaluate
+    /// them.
+    pub fn genomes_mut(&mut self) -> &mut [G] {
+        &mut self.genomes
+    }
+
+    /// Returns the number of the current generation, starting at zero.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Sets the number of the current generation, e.g. when resuming a previous run.
+    pub fn set_generation(&mut self, generation: u32) {
+        self.generation = generation;
+    }
+
+    /// Returns the genome with the highest fitness. A NaN fitness counts as higher than any other.
+    pub fn best(&self) -> Option<&G> {
+        self.genomes
+            .iter()
+            .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+    }
+
+    /// Sorts the genomes from the fittest one. Genomes with a NaN fitness come first.
+    pub fn sort_by_fitness(&mut self) {
+        self.genomes
+            .sort_unstable_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+    }
+
+    /// Replaces the current generation with a new one of [`size`] genomes. The elite genomes are
+    /// carried over without mutation, every other child is a mutated crossover of two parents
+    /// chosen by the [`SelectionStrategy`]. With speciation both parents come from the same
+    /// species.
+    ///
+    /// Children are bred in parallel, each with its own random number generator seeded from
+    /// `rng`, so the result only depends on `rng` and not on the number of threads.
+    ///
+    /// [`size`]: #method.size
+    /// [`SelectionStrategy`]: enum.SelectionStrategy.html
+    ///
+    /// # Panics
+    ///
+    /// Panics if the population has less than two genomes.
+    pub fn evolve<R: Rng + ?Sized>(&mut self, rng: &mut R)
+    where
+        G: Send + Sync,
+    {
+        assert!(self.genomes.len() >= 2, "at least two genomes are required");
+
+        self.sort_by_fitness();
+
+        let elite_count = self.elite_count.min(self.genomes.len()).min(self.size);
+        let mutation = self.mutation.at_generation(self.generation);
+
+        let mut children = Vec::with_capacity(self.size);
+        children.extend_from_slice(&self.genomes[..elite_count]);
+
+        let species = match self.species_threshold {
+            Some(threshold) => self.speciate(threshold),
+            None => std::iter::once(0..self.genomes.len()).collect(),
+        };
+        let offspring = Self::allocate_offspring(&self.genomes, &species, self.size - elite_count);
+
+        let mut matings = Vec::with_capacity(self.size - elite_count);
+        for (range, count) in species.iter().zip(offspring) {
+            let members = &self.genomes[range.clone()];
+            for _ in 0..count {
+                let parent1 = range.start + self.selection.select(members, 0, rng);
+                let parent2 = range.start + self.selection.select(members, 1, rng);
+                matings.push((parent1, parent2, rng.gen::<u64>()));
+            }
+        }
+
+        let genomes = &self.genomes;
+        children.par_extend(matings.into_par_iter().map(|(parent1, parent2, seed)| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut genome = genomes[parent1].crossover(&genomes[parent2], &mut rng);
+            genome.mutate(&mutation, &mut rng);
+
+            genome
+        }));
+
+        self.species_count = species.len();
+        self.genomes = children;
+        self.generation += 1;
+    }
+
+    /// Groups the genomes, which must be sorted from the fittest one, into species and reorders
+    /// them so that every species is a contiguous range still sorted by fitness. A genome joins
+    /// the first species whose fittest genome is closer than `threshold`.
+    fn speciate(&mut self, threshold: f32) -> Vec<Range<usize>> {
+        let mut representatives: Vec<usize> = Vec::new();
+        let mut species = Vec::with_capacity(self.genomes.len());
+
+        for (i, genome) in self.genomes.iter().enumerate() {
+            let id = representatives
+                .iter()
+                .position(|&r| self.genomes[r].distance(genome) < threshold)
+                .unwrap_or_else(|| {
+                    representatives.push(i);
+                    representatives.len() - 1
+                });
+            species.push(id);
+        }
+
+        // The sort is stable, so every species stays sorted by fitness.
+        let mut order: Vec<usize> = (0..self.genomes.len()).collect();
+        order.sort_by_key(|&i| species[i]);
+        self.genomes = order.iter().map(|&i| self.genomes[i].clone()).collect();
+
+        let mut ranges = Vec::with_capacity(representatives.len());
+        let mut start = 0;
+        for id in 0..representatives.len() {
+            let size = species.iter().filter(|&&s| s == id).count();
+            ranges.push(start..start + size);
+            start += size;
+        }
+
+        ranges
+    }
+
+    /// Splits `total` children among the species proportionally to their shared fitness, which
+    /// is the mean fitness of the species. Children left over by rounding go to the first
+    /// species, which holds the fittest genome.
+    fn allocate_offspring(genomes: &[G], species: &[Range<usize>], total: usize) -> Vec<usize> {
+        let shares: Vec<f32> = species
+            .iter()
+            .map(|range| {
+                let sum: f32 = genomes[range.clone()]
+                    .iter()
+                    .map(|g| g.fitness().max(0.0))
+                    .sum();
+                sum / range.len() as f32
+            })
+            .collect();
+        let total_share: f32 = shares.iter().sum();
+
+        let mut offspring: Vec<usize> = if total_share > 0.0 {
+            shares
+                .iter()
+                .map(|share| (total as f32 * share / total_share) as usize)
+                .collect()
+        } else {
+            species
+                .iter()
+                .map(|range| total * range.len() / genomes.len())
+                .collect()
+        };
+
+        let allocated: usize = offspring.iter().sum();
+        offspring[0] += total.saturating_sub(allocated);
+
+        offspring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Number(f32);
+
+    impl Genome for Number {
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn crossover<R: Rng + ?Sized>(&self, other: &Self, _rng: &mut R) -> Self {
+            Number((self.0 + other.0) / 2.0)
+        }
+
+        fn mutate<R: Rng + ?Sized>(&mut self, _config: &MutationConfig, _rng: &mut R) {
+            self.0 += 1.0;
+        }
+
+        fn distance(&self, other: &Self) -> f32 {
+            (self.0 - other.0).abs()
+        }
+    }
+
+    fn population() -> Population<Number> {
+        Population::new(vec![Number(1.0), Number(4.0), Number(2.0), Number(6.0)])
+    }
+
+    #[test]
+    fn test_best() {
+        assert_eq!(population().best(), Some(&Number(6.0)));
+    }
+
+    #[test]
+    fn test_sort_with_nan() {
+        let mut population = Population::new(vec![Number(1.0), Number(f32::NAN), Number(3.0)]);
+
+        population.sort_by_fitness();
+
+        assert!(population.best().unwrap().0.is_nan());
+        assert_eq!(&population.genomes()[1..], &[Number(3.0), Number(1.0)]);
+    }
+
+    #[test]
+    fn test_evolve() {
+        let mut population = population();
+
+        population.evolve(&mut rand::thread_rng());
+
+        assert_eq!(population.generation(), 1);
+        assert_eq!(population.genomes(), &[Number(6.0); 4]);
+    }
+
+    #[test]
+    fn test_evolve_keeps_elite() {
+        let mut population = population();
+        population.set_elite_count(2);
+
+        population.evolve(&mut rand::thread_rng());
+
+        assert_eq!(
+            population.genomes(),
+            &[Number(6.0), Number(4.0), Number(6.0), Number(6.0)]
+        );
+    }
+
+    #[test]
+    fn test_evolve_resizes() {
+        let mut population = population();
+        population.set_elite_count(2);
+        population.set_size(6);
+
+        population.evolve(&mut rand::thread_rng());
+        assert_eq!(population.genomes().len(), 6);
+
+        population.set_size(3);
+        population.evolve(&mut rand::thread_rng());
+        assert_eq!(population.genomes().len(), 3);
+    }
+
+    #[test]
+    fn test_evolve_species() {
+        let mut population = population();
+        population.set_species_threshold(Some(1.5));
+
+        population.evolve(&mut rand::thread_rng());
+
+        // Species are {6}, {4} and {2, 1}. The last one gets no child because of its low mean
+        // fitness, the child left over by rounding goes to the fittest species.
+        assert_eq!(population.species_count(), 3);
+        assert_eq!(
+            population.genomes(),
+            &[Number(7.0), Number(7.0), Number(7.0), Number(5.0)]
+        );
+    }
+
+    #[test]
+    fn test_tournament_of_whole_population() {
+        let mut population = population();
+        population.sort_by_fitness();
+
+        let picked = SelectionStrategy::Tournament(64).select(
+            population.genomes(),
+            0,
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(picked, 0);
+    }
+
+    #[test]
+    fn test_roulette_skips_zero_fitness() {
+        let genomes = [Number(3.0), Number(1.0), Number(0.0), Number(-5.0)];
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            assert!(SelectionStrategy::Roulette.select(&genomes, 0, &mut rng) < 2);
+        }
+    }
+
+    #[test]
+    fn test_rank_in_bounds() {
+        let genomes = [Number(3.0), Number(1.0), Number(0.0)];
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            assert!(SelectionStrategy::Rank.select(&genomes, 0, &mut rng) < genomes.len());
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Actions of the game and the keys bound to them.
+//!
+//! Games ask the [`InputMap`] of their context whether an [`Action`] is pressed instead of looking
+//! at keys, so that the keys can be changed in the `keys` section of the config:
+//!
+//! ```toml
+//! [keys]
+//! pause = "Space"
+//! speed_up = "L"
+//! ```
+//!
+//! [`InputMap`]: struct.InputMap.html
+//! [`Action`]: enum.Action.html
+
+use crate::window::GameWindow;
+use crate::Error;
+use sdl2::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Something the player can do with a key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Closes the window.
+    Quit,
+
+    /// Leaves the current mode.
+    Back,
+
+    /// Shows or hides the list of keys.
+    ToggleHelp,
+
+    /// Speeds up the simulation.
+    SpeedUp,
+
+    /// Slows down the simulation.
+    SlowDown,
+
+    /// Pauses or resumes the simulation.
+    Pause,
+
+    /// Advances a paused simulation by one update.
+    Step,
+
+    /// Skips generations without drawing them.
+    FastForward,
+
+    /// Shows or hides debug information.
+    ToggleDebug,
+
+    /// Toggles vertical sync.
+    ToggleVsync,
+
+    /// Zooms in.
+    ZoomIn,
+
+    /// Zooms out.
+    ZoomOut,
+
+    /// Resets the zoom.
+    ResetZoom,
+
+    /// Shows or hides the tuning panel.
+    ToggleTuning,
+
+    /// Opens or closes the developer console.
+    ToggleConsole,
+
+    /// Selects the previous entry.
+    Up,
+
+    /// Selects the next entry.
+    Down,
+
+    /// Increases the selected value.
+    Increase,
+
+    /// Decreases the selected value.
+    Decrease,
+
+    /// Chooses the selected entry.
+    Confirm,
+}
+
+impl Action {
+    /// Number of actions.
+    pub const COUNT: usize = 20;
+
+    /// All actions in the order they are listed in the help.
+    pub const ALL: [Action; Self::COUNT] = [
+        Action::Quit,
+        Action::Back,
+        Action::ToggleHelp,
+        Action::SpeedUp,
+        Action::SlowDown,
+        Action::Pause,
+        Action::Step,
+        Action::FastForward,
+        Action::ToggleDebug,
+        Action::ToggleVsync,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetZoom,
+        Action::ToggleTuning,
+        Action::ToggleConsole,
+        Action::Up,
+        Action::Down,
+        Action::Increase,
+        Action::Decrease,
+        Action::Confirm,
+    ];
+
+    /// Returns the name of this action in the config.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Back => "back",
+            Action::ToggleHelp => "toggle_help",
+            Action::SpeedUp => "speed_up",
+            Action::SlowDown => "slow_down",
+            Action::Pause => "pause",
+            Action::Step => "step",
+            Action::FastForward => "fast_forward",
+            Action::ToggleDebug => "toggle_debug",
+            Action::ToggleVsync => "toggle_vsync",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ResetZoom => "reset_zoom",
+            Action::ToggleTuning => "toggle_tuning",
+            Action::ToggleConsole => "toggle_console",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Increase => "increase",
+            Action::Decrease => "decrease",
+            Action::Confirm => "confirm",
+        }
+    }
+
+    /// Returns what this action does as shown in the help.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Leave the mode or close this help",
+            Action::ToggleHelp => "Show or hide this help",
+            Action::SpeedUp => "Speed up the simulation",
+            Action::SlowDown => "Slow down the simulation",
+            Action::Pause => "Pause or resume",
+            Action::Step => "Advance a paused simulation by one update",
+            Action::FastForward => "Skip the next generations without drawing",
+            Action::ToggleDebug => "Show the frame rate and profile",
+            Action::ToggleVsync => "Toggle vertical sync",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::ResetZoom => "Reset the zoom",
+            Action::ToggleTuning => "Show the tuning panel",
+            Action::ToggleConsole => "Open the developer console",
+            Action::Up => "Select the previous entry",
+            Action::Down => "Select the next entry",
+            Action::Increase => "Increase the selected parameter",
+            Action::Decrease => "Decrease the selected parameter",
+            Action::Confirm => "Choose the selected entry",
+        }
+    }
+
+    /// Returns the key bound to this action unless the config changes it.
+    pub fn default_key(self) -> Keycode {
+        match self {
+            Action::Quit => Keycode::Q,
+            Action::Back => Keycode::Escape,
+            Action::ToggleHelp => Keycode::H,
+            Action::SpeedUp => Keycode::K,
+            Action::SlowDown => Keycode::J,
+            Action::Pause => Keycode::P,
+            Action::Step => Keycode::N,
+            Action::FastForward => Keycode::F,
+            Action::ToggleDebug => Keycode::F3,
+            Action::ToggleVsync => Keycode::V,
+            Action::ZoomIn => Keycode::Equals,
+            Action::ZoomOut => Keycode::Minus,
+            Action::ResetZoom => Keycode::Num0,
+            Action::ToggleTuning => Keycode::F1,
+            Action::ToggleConsole => Keycode::Backquote,
+            Action::Up => Keycode::Up,
+            Action::Down => Keycode::Down,
+            Action::Increase => Keycode::Right,
+            Action::Decrease => Keycode::Left,
+            Action::Confirm => Keycode::Return,
+        }
+    }
+}
+
+/// The key of every action and which actions are pressed in the current frame.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::input::{Action, InputMap};
+/// use sdl2::keyboard::Keycode;
+///
+/// let mut input = InputMap::default();
+/// input.bind(Action::Pause, Keycode::Space);
+///
+/// assert_eq!(input.key(Action::Pause), Keycode::Space);
+/// assert_eq!(input.key(Action::Quit), Keycode::Q);
+/// assert!(!input.is_action_pressed(Action::Pause));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputMap {
+    keys: [Keycode; Action::COUNT],
+    pressed: [bool; Action::COUNT],
+    just_pressed: [bool; Action::COUNT],
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL.map(Action::default_key),
+            pressed: [false; Action::COUNT],
+            just_pressed: [false; Action::COUNT],
+        }
+    }
+}
+
+impl InputMap {
+    /// Creates a map with the default keys, except for the actions in `names` which are bound
+    /// to the keys of the given names, e.g. `"Space"` or `"F5"`. Fails if a name is not a key or
+    /// two actions end up on the same key.
+    pub fn from_names(names: &BTreeMap<Action, String>) -> Result<Self, Error> {
+        let mut input = Self::default();
+        for (&action, name) in names {
+            let key = Keycode::from_name(name).ok_or_else(|| {
+                Error::Config(format!("unknown key {} for {}", name, action.name()))
+            })?;
+            input.bind(action, key);
+        }
+
+        for (i, &action) in Action::ALL.iter().enumerate() {
+            let key = input.key(action);
+            if let Some(&other) = Action::ALL[i + 1..]
+                .iter()
+                .find(|&&other| input.key(other) == key)
+            {
+                return Err(Error::Config(format!(
+                    "{} and {} are bound to the same key",
+                    action.name(),
+                    other.name()
+                )));
+            }
+        }
+
+        Ok(input)
+    }
+
+    /// Binds `action` to `key`.
+    pub fn bind(&mut self, action: Action, key: Keycode) {
+        self.keys[action as usize] = key;
+    }
+
+    /// Returns the key bound to `action`.
+    pub fn key(&self, action: Action) -> Keycode {
+        self.keys[action as usize]
+    }
+
+    /// Reads which actions are pressed from the keys polled by `window`. Called by the game loop
+    /// after every poll.
+    pub fn update(&mut self, window: &GameWindow) {
+        for action in Action::ALL.iter() {
+            let key = self.key(*action);
+            self.pressed[*action as usize] = window.is_key_pressed(&key);
+            self.just_pressed[*action as usize] = window.was_key_just_pressed(&key);
+        }
+    }
+
+    /// Returns true if the key of `action` is held down.
+    pub fn is_action_pressed(&self, action: Action) -> bool {
+        self.pressed[action as usize]
+    }
+
+    /// Returns true if the key of `action` was pressed since the previous poll.
+    pub fn was_action_just_pressed(&self, action: Action) -> bool {
+        self.just_pressed[action as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keys_unique() {
+        assert!(InputMap::from_names(&BTreeMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_all_in_order() {
+        for (i, action) in Action::ALL.iter().enumerate() {
+            assert_eq!(*action as usize, i);
+        }
+    }
+}
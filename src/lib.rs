@@ -3,6 +3,26 @@
 
 #![warn(missing_docs)]
 
+mod error;
+
+pub mod checkpoint;
+pub mod config;
+pub mod console;
+pub mod curriculum;
+pub mod fitness;
+pub mod game;
+pub mod genetic;
+pub mod input;
 pub mod math;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod neuralnet;
+pub mod profiler;
+#[cfg(feature = "recorder")]
+pub mod recorder;
+pub mod replay;
+pub mod scene;
+pub mod stats;
 pub mod window;
+
+pub use error::Error;
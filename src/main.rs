@@ -1,102 +1,296 @@
+use dinai::checkpoint::Checkpoint;
+use dinai::config::{Config, ConfigWatcher, ObstacleSettings, SimulationSettings};
+use dinai::console::Console;
+use dinai::curriculum::Curriculum;
+use dinai::fitness::{Achievements, FitnessFn, WeightedFitness};
+use dinai::game::{Context, FastForward, GameLoop, SimulationState};
+use dinai::genetic::{Genome, MutationConfig, Population};
+use dinai::input::Action;
 use dinai::math::{AABBf, Matrix, Vector2f};
-use dinai::neuralnet::NeuralNetwork;
-use dinai::window::{GameWindow, TextRenderer, WindowConfig};
+#[cfg(feature = "metrics")]
+use dinai::metrics::{self, Metrics};
+use dinai::neuralnet::{InputNormalizer, RecurrentNetwork};
+use dinai::profiler::{Phase, ProfileRecorder, Profiler};
+use dinai::replay::{Replay, Spawn};
+use dinai::scene::{Scene, SceneStack, Transition};
+use dinai::stats::{GenerationStats, StatsHistory, StatsRecorder};
+use dinai::window::{Camera, TextAlign, TextStyle};
+use dinai::Error;
+use log::{debug, info, warn, LevelFilter, Log, Metadata, Record};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rayon::prelude::*;
-use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
-use sdl2::rect::Rect;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::BlendMode;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::mem;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
 use std::time::Instant;
+use std::vec;
 
-const GRAVITY: f32 = 800.0;
+const CHECKPOINT_DIR: &str = "checkpoints";
+// A checkpoint with the best networks is written every this many generations.
+const CHECKPOINT_INTERVAL: u32 = 10;
+const CHECKPOINT_TOP: usize = 5;
 
-struct Context<'a> {
-    game_window: &'a mut GameWindow,
-    text_renderer: &'a TextRenderer<'a>,
-    step_s: f32,
-    speed: f32,
-}
+// Inputs are the `Sensors` of a player. Outputs are jumping and ducking.
+// The network remembers what it saw during the life of its player.
+type Network = RecurrentNetwork<{ Sensors::COUNT }, 4, 2>;
+
+// Time survived, jumps per second and obstacle sizes above these are fed to
+// the network as if they were equal to them.
+const MAX_SURVIVAL_INPUT_S: f32 = 60.0;
+const MAX_JUMP_RATE_INPUT: f32 = 2.0;
+const MAX_OBSTACLE_SIZE_INPUT: f32 = 100.0;
+
+const PLAYER_HEIGHT: f32 = 25.0;
+// Height of a ducking player, low enough to pass under birds.
+const DUCK_HEIGHT: f32 = 12.0;
+
+// Number of most recent generations shown in the fitness plot.
+const PLOT_GENERATIONS: usize = 100;
+
+// Color of the players that are neither leading nor the previous champion.
+const CROWD_COLOR: Color = Color::RGBA(100, 100, 100, 70);
+
+// Color of the best player of the previous generation.
+const CHAMPION_COLOR: Color = Color::RGB(150, 0, 200);
+
+// Colors of the leading players, from the first one.
+const LEADER_COLORS: [Color; 5] = [
+    Color::RGB(220, 40, 40),
+    Color::RGB(240, 140, 0),
+    Color::RGB(200, 180, 0),
+    Color::RGB(40, 160, 40),
+    Color::RGB(40, 90, 220),
+];
+
+// Metrics are served here unless another address is given.
+#[cfg(feature = "metrics")]
+const METRICS_ADDR: &str = "127.0.0.1:9898";
 
+// By default every other frame of a 60 Hz display is recorded.
+#[cfg(feature = "recorder")]
+const RECORD_INTERVAL: u32 = 2;
+
+// Number of generations skipped by the fast-forward hotkey.
+const FAST_FORWARD_GENERATIONS: u32 = 10;
+
+#[derive(Clone)]
 enum MovementState {
     Running,
     Jumping,
+    Ducking,
 }
 
+#[derive(Clone)]
 struct Player {
     pos: Vector2f,
     size: Vector2f,
     state: MovementState,
     alive: bool,
     score: f32,
+    achievements: Achievements,
+
+    // The last obstacle counted as cleared and the smallest gap between it
+    // and the player so far.
+    cleared_obstacle: Option<u32>,
+    closest_gap: f32,
 
     // Defined as pixels per second.
     velocity: Vector2f,
 
-    nnet: NeuralNetwork<3, 4, 1>,
+    // Whether the jump output is held and for how long the current jump
+    // lasts in seconds.
+    holding_jump: bool,
+    jump_time: f32,
+
+    // Controls decided for the current step and of every step, kept only
+    // while the environment records them.
+    controls: Controls,
+    actions: Vec<Controls>,
+
+    color: Color,
+
+    nnet: Network,
+}
+
+/// What a player decided to do in one step. Serialized as bit flags to
+/// keep replays small.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "u8", into = "u8")]
+struct Controls {
+    jump: bool,
+    duck: bool,
+}
+
+impl From<u8> for Controls {
+    fn from(bits: u8) -> Self {
+        Self {
+            jump: bits & 1 != 0,
+            duck: bits & 2 != 0,
+        }
+    }
+}
+
+impl From<Controls> for u8 {
+    fn from(controls: Controls) -> Self {
+        controls.jump as u8 | (controls.duck as u8) << 1
+    }
 }
 
 impl Player {
-    fn draw(&self, ctx: &mut Context, interpolation: f32) -> Result<(), String> {
-        let canvas = ctx.game_window.canvas_mut();
+    fn new(floor_bot_y: f32, mut nnet: Network) -> Self {
+        nnet.reset();
+
+        Self {
+            pos: Vector2f::from_coords(100.0, floor_bot_y - PLAYER_HEIGHT),
+            size: Vector2f::from_coords(25.0, PLAYER_HEIGHT),
+            state: MovementState::Running,
+            alive: true,
+            score: 0.0,
+            achievements: Achievements::default(),
+            cleared_obstacle: None,
+            closest_gap: f32::INFINITY,
+            velocity: Vector2f::new(),
+            holding_jump: false,
+            jump_time: 0.0,
+            controls: Controls::default(),
+            actions: Vec::new(),
+            color: Color::RGB(0, 0, 0),
+            nnet,
+        }
+    }
 
-        let pos = self.pos + self.velocity * interpolation;
+    /// Puts this player back to the start keeping its network.
+    fn reset(&mut self, floor_bot_y: f32) {
+        *self = Self::new(floor_bot_y, mem::take(&mut self.nnet));
+    }
 
-        canvas.set_draw_color(Color::RGB(0, 0, 0));
-        canvas.fill_rect(Rect::new(
-            pos.x as i32,
-            pos.y as i32,
-            self.size.x as u32,
-            self.size.y as u32,
-        ))?;
+    fn draw(&self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        let bb = self.aabbf().translate(self.velocity * interpolation);
 
-        Ok(())
+        ctx.game_window.fill_rect_blended(&bb, self.color)
     }
 
-    fn think(&mut self, environment: &Environment) {
-        let pos_y = self.pos.y;
-        let obstacle_dx = environment.obstacle.pos.x - self.pos.x;
-        let score = self.score;
+    fn think(&mut self, environment: &Environment) -> Controls {
+        let input = Sensors::read(self, environment).to_input();
+        let output = self.nnet.feed(&environment.normalizer.normalize(&input));
+        let output = output.as_ref()[0];
 
-        let input = Matrix::from([[pos_y, obstacle_dx, score]]);
-        let output = self.nnet.feed(&input);
-        if output.as_ref()[0][0] > 0.75 {
-            self.jump();
+        Controls {
+            jump: output[0] > 0.75,
+            duck: output[1] > 0.75,
         }
     }
 
     fn update(&mut self, step_s: f32, environment: &Environment) {
-        if self.aabbf().intersects(&environment.obstacle.aabbf()) {
+        self.decide(step_s, environment);
+        if self.alive {
+            self.act(self.controls, step_s, environment);
+        }
+    }
+
+    /// Kills the player if it hits the obstacle during the next step,
+    /// otherwise decides its controls for the step.
+    fn decide(&mut self, step_s: f32, environment: &Environment) {
+        if self.collides(step_s, environment) {
             self.alive = false;
             return;
         }
 
-        self.think(environment);
+        self.controls = self.think(environment);
+        if environment.record_actions {
+            self.actions.push(self.controls);
+        }
+    }
+
+    /// Returns true if the player hits the obstacle during the next step.
+    fn collides(&self, step_s: f32, environment: &Environment) -> bool {
+        // The obstacle is fast enough to pass through the player within one step, so the whole
+        // movement of this step is tested.
+        let displacement = (self.velocity - environment.obstacle.velocity()) * step_s;
+        let obstacle_bb = environment.obstacle.aabbf();
+
+        self.aabbf().sweep(displacement, &obstacle_bb).is_some()
+    }
+
+    /// Moves the player by one step as told by `controls`.
+    fn act(&mut self, controls: Controls, step_s: f32, environment: &Environment) {
+        self.duck(controls.duck);
+        self.holding_jump = controls.jump;
+        if self.holding_jump {
+            self.jump(environment.simulation.jump_velocity);
+        }
 
         if let MovementState::Jumping = self.state {
-            self.velocity.y += GRAVITY * step_s;
+            // Holding the jump while rising reduces gravity, so the player
+            // jumps higher and further.
+            let simulation = &environment.simulation;
+            let mut gravity = simulation.gravity;
+            if self.holding_jump
+                && self.velocity.y < 0.0
+                && self.jump_time < simulation.max_jump_hold_s
+            {
+                gravity *= simulation.jump_hold_gravity;
+            }
+
+            self.velocity.y += gravity * step_s;
+            self.jump_time += step_s;
 
             // Predict collision one frame in advance. This way the player
             // does not flicker after landing on the floor.
-            let future_pos = self.pos + self.velocity * step_s;
-
-            let bb = AABBf {
-                min: future_pos,
-                max: future_pos + self.size,
-            };
+            let bb = self.aabbf().translate(self.velocity * step_s);
 
             let floor_bb = &environment.floor.bounding_box;
 
-            // Player intersects with floor.
-            if bb.intersects(floor_bb) {
+            // Player intersects with floor, push it out.
+            if let Some(contact) = bb.contact(floor_bb) {
                 self.velocity.y = 0.0;
-                self.pos.y = floor_bb.min.y - self.size.y;
+                self.pos = bb.min + contact.normal * contact.depth;
                 self.state = MovementState::Running;
             }
         }
 
-        self.score += step_s;
-
         self.velocity.x = 0.0;
         self.pos += self.velocity * step_s;
+
+        self.achieve(step_s, environment);
+    }
+
+    /// Updates the achievements of the last step and the score computed
+    /// from them.
+    fn achieve(&mut self, step_s: f32, environment: &Environment) {
+        let obstacle_bb = environment.obstacle.aabbf();
+        let bb = self.aabbf();
+
+        self.achievements.survival_s += step_s;
+        self.achievements.distance -= environment.obstacle.velocity_x * step_s;
+
+        if self.cleared_obstacle != Some(environment.obstacle_id) {
+            if obstacle_bb.max.x < bb.min.x {
+                self.cleared_obstacle = Some(environment.obstacle_id);
+                self.achievements.obstacles_cleared += 1;
+                if self.closest_gap < environment.fitness.weights().close_call_distance {
+                    self.achievements.close_calls += 1;
+                }
+                self.closest_gap = f32::INFINITY;
+            } else if obstacle_bb.min.x <= bb.max.x {
+                // Vertical gap while the obstacle passes under or over the player.
+                let gap = (obstacle_bb.min.y - bb.max.y).max(bb.min.y - obstacle_bb.max.y);
+                self.closest_gap = self.closest_gap.min(gap);
+            }
+        }
+
+        self.score = environment.fitness.fitness(&self.achievements);
     }
 
     fn aabbf(&self) -> AABBf {
@@ -106,12 +300,121 @@ impl Player {
         }
     }
 
-    fn jump(&mut self) {
+    fn jump(&mut self, velocity_y: f32) {
         if let MovementState::Running = self.state {
-            self.velocity.y = -350.0;
+            self.velocity.y = velocity_y;
+            self.jump_time = 0.0;
+            self.achievements.jumps += 1;
             self.state = MovementState::Jumping;
         }
     }
+
+    /// Shrinks the player while `ducking` holds and stands it back up
+    /// otherwise. The bottom of the player stays on the floor.
+    fn duck(&mut self, ducking: bool) {
+        match (&self.state, ducking) {
+            (MovementState::Running, true) => {
+                self.pos.y += PLAYER_HEIGHT - DUCK_HEIGHT;
+                self.size.y = DUCK_HEIGHT;
+                self.state = MovementState::Ducking;
+            }
+            (MovementState::Ducking, false) => {
+                self.pos.y -= PLAYER_HEIGHT - DUCK_HEIGHT;
+                self.size.y = PLAYER_HEIGHT;
+                self.state = MovementState::Running;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Everything a player perceives, in the order it is fed to the network.
+struct Sensors {
+    pos_y: f32,
+    obstacle_dx: f32,
+    obstacle_y: f32,
+    obstacle_speed: f32,
+    obstacle_width: f32,
+    obstacle_height: f32,
+    survival_s: f32,
+    jump_rate: f32,
+}
+
+impl Sensors {
+    /// Number of network inputs.
+    const COUNT: usize = 8;
+
+    fn read(player: &Player, environment: &Environment) -> Self {
+        let obstacle = &environment.obstacle;
+        let survival_s = player.achievements.survival_s;
+
+        Self {
+            pos_y: player.pos.y,
+            obstacle_dx: obstacle.pos.x - player.pos.x,
+            obstacle_y: obstacle.pos.y,
+            obstacle_speed: -obstacle.velocity_x,
+            obstacle_width: obstacle.size.x,
+            obstacle_height: obstacle.size.y,
+            survival_s,
+            // Counted over at least a second, so the first jump is not a spike.
+            jump_rate: player.achievements.jumps as f32 / survival_s.max(1.0),
+        }
+    }
+
+    /// Creates the normalizer of the sensors in a world of the given size
+    /// with obstacles up to `max_speed`. Fails unless all of them are
+    /// positive.
+    fn normalizer(
+        width: f32,
+        floor_bot_y: f32,
+        max_speed: f32,
+    ) -> Result<InputNormalizer<{ Self::COUNT }>, Error> {
+        InputNormalizer::new([
+            (0.0, floor_bot_y),
+            (0.0, width),
+            (0.0, floor_bot_y),
+            (0.0, max_speed),
+            (0.0, MAX_OBSTACLE_SIZE_INPUT),
+            (0.0, MAX_OBSTACLE_SIZE_INPUT),
+            (0.0, MAX_SURVIVAL_INPUT_S),
+            (0.0, MAX_JUMP_RATE_INPUT),
+        ])
+    }
+
+    fn to_input(&self) -> Matrix<f32, 1, { Self::COUNT }> {
+        Matrix::from([[
+            self.pos_y,
+            self.obstacle_dx,
+            self.obstacle_y,
+            self.obstacle_speed,
+            self.obstacle_width,
+            self.obstacle_height,
+            self.survival_s,
+            self.jump_rate,
+        ]])
+    }
+}
+
+impl Genome for Player {
+    fn fitness(&self) -> f32 {
+        self.score
+    }
+
+    // The child takes over the state of `self`, the game resets every
+    // player before the next generation starts.
+    fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self {
+        let mut child = self.clone();
+        child.nnet = self.nnet.crossover(&other.nnet, rng);
+        child
+    }
+
+    fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R) {
+        self.nnet.mutate(config, rng);
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        self.nnet.distance(&other.nnet)
+    }
 }
 
 struct Floor {
@@ -121,23 +424,23 @@ struct Floor {
 }
 
 impl Floor {
-    fn draw(&self, ctx: &mut Context) -> Result<(), String> {
-        let bb = &self.bounding_box;
-        let canvas = ctx.game_window.canvas_mut();
+    fn draw(&self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.game_window
+            .fill_rect(&self.bounding_box, Color::RGB(55, 55, 55))
+    }
+}
 
-        canvas.set_draw_color(Color::RGB(55, 55, 55));
-        canvas.fill_rect(Rect::new(
-            bb.min.x as i32,
-            bb.min.y as i32,
-            (bb.max.x - bb.min.x) as u32,
-            (bb.max.y - bb.min.y) as u32,
-        ))?;
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+enum ObstacleKind {
+    // Stands on the floor and has to be jumped over.
+    Cactus,
 
-        Ok(())
-    }
+    // Flies at head height, the player can either jump over it or duck.
+    Bird,
 }
 
 struct Obstacle {
+    kind: ObstacleKind,
     pos: Vector2f,
     size: Vector2f,
 
@@ -146,31 +449,43 @@ struct Obstacle {
 }
 
 impl Obstacle {
-    fn draw(&self, ctx: &mut Context, interpolation: f32) -> Result<(), String> {
-        let canvas = ctx.game_window.canvas_mut();
-
-        let x_pos = self.pos.x + self.velocity_x * interpolation;
+    fn draw(&self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        let bb = self.aabbf().translate(self.velocity() * interpolation);
+        let color = match self.kind {
+            ObstacleKind::Cactus => Color::RGB(0, 127, 0),
+            ObstacleKind::Bird => Color::RGB(127, 64, 0),
+        };
 
-        canvas.set_draw_color(Color::RGB(0, 127, 0));
-        canvas.fill_rect(Rect::new(
-            x_pos as i32,
-            self.pos.y as i32,
-            self.size.x as u32,
-            self.size.y as u32,
-        ))?;
+        ctx.game_window.fill_rect(&bb, color)
+    }
 
-        Ok(())
+    /// Places a new obstacle of the given kind at `x` on a floor whose top
+    /// is at `floor_bot_y`.
+    fn spawn(&mut self, kind: ObstacleKind, x: f32, floor_bot_y: f32) {
+        self.kind = kind;
+        match kind {
+            ObstacleKind::Cactus => {
+                self.size = Vector2f::from_coords(25.0, 35.0);
+                self.pos = Vector2f::from_coords(x, floor_bot_y - 35.0);
+            }
+            ObstacleKind::Bird => {
+                // Hits a standing player but passes over a ducking one.
+                self.size = Vector2f::from_coords(40.0, 20.0);
+                self.pos = Vector2f::from_coords(x, floor_bot_y - DUCK_HEIGHT - 5.0 - 20.0);
+            }
+        }
     }
 
-    fn update(&mut self, ctx: &Context) {
-        self.pos.x += self.velocity_x * ctx.step_s;
+    /// Returns true if the obstacle has left the world on the left.
+    fn is_gone(&self) -> bool {
+        self.pos.x + self.size.x < 0.0
+    }
 
-        if self.pos.x + self.size.x < 0.0 {
-            self.pos.x = ctx.game_window.config().width as f32;
-        }
+    fn update(&mut self, step_s: f32, settings: &ObstacleSettings) {
+        self.pos.x += self.velocity_x * step_s;
 
-        if self.velocity_x > -2000.0 {
-            self.velocity_x -= 30.0 * ctx.step_s;
+        if self.velocity_x > -settings.max_speed {
+            self.velocity_x -= settings.acceleration * step_s;
         }
     }
 
@@ -180,216 +495,2273 @@ impl Obstacle {
             max: self.pos + self.size,
         }
     }
-}
 
-trait Game {
-    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), String>;
-    fn handle_input(&mut self, ctx: &mut Context) -> Result<(), String>;
-    fn update(&mut self, ctx: &mut Context) -> Result<(), String>;
+    fn velocity(&self) -> Vector2f {
+        Vector2f::from_coords(self.velocity_x, 0.0)
+    }
 }
 
 struct Environment {
+    // Obstacles respawn at the right edge of the world.
+    width: f32,
+    simulation: SimulationSettings,
+    obstacle_settings: ObstacleSettings,
     floor: Floor,
     obstacle: Obstacle,
-}
 
-struct DinaiGame {
-    players: Vec<Player>,
-    generation: u32,
-    environment: Environment,
+    // Decides the kind of every spawned obstacle.
+    rng: StdRng,
+
+    // Increases with every spawned obstacle, so players can tell when they
+    // cleared a new one.
+    obstacle_id: u32,
+
+    // Steps since the last restart and the obstacles spawned in them.
+    step: u32,
+    spawns: Vec<Spawn<ObstacleKind>>,
+
+    // Kinds of the obstacles to spawn instead of drawing them from `rng`
+    // when playing a replay.
+    script: Option<vec::IntoIter<ObstacleKind>>,
+
+    // Whether players keep their controls of every step for replays.
+    record_actions: bool,
+
+    fitness: WeightedFitness,
+    normalizer: InputNormalizer<{ Sensors::COUNT }>,
 }
 
-impl DinaiGame {
-    fn new(ctx: &mut Context) -> Self {
-        let win_width = ctx.game_window.config().width;
+impl Environment {
+    fn new(config: &Config, seed: u64) -> Result<Self, Error> {
+        let width = config.window.width as f32;
 
         let floor = Floor {
             bounding_box: AABBf {
                 min: Vector2f::from_coords(0.0, 600.0),
-                max: Vector2f::from_coords(win_width as f32, 620.0),
+                max: Vector2f::from_coords(width, 620.0),
             },
         };
         let floor_bot_y = floor.bounding_box.min.y;
 
-        let mut players = Vec::new();
-        for _ in 0..1000 {
-            players.push(Player {
-                pos: Vector2f::from_coords(100.0, floor_bot_y - 25.0),
-                size: Vector2f::from_coords(25.0, 25.0),
-                state: MovementState::Running,
-                alive: true,
-                score: 0.0,
-                velocity: Vector2f::new(),
-                nnet: NeuralNetwork::new(),
-            });
-        }
+        let mut obstacle = Obstacle {
+            kind: ObstacleKind::Cactus,
+            pos: Vector2f::new(),
+            size: Vector2f::new(),
+            velocity_x: -config.obstacle.speed,
+        };
+        obstacle.spawn(ObstacleKind::Cactus, width, floor_bot_y);
+
+        Ok(Self {
+            width,
+            simulation: config.simulation.clone(),
+            obstacle_settings: config.obstacle.clone(),
+            floor,
+            obstacle,
+            rng: StdRng::seed_from_u64(seed),
+            obstacle_id: 0,
+            step: 0,
+            spawns: Vec::new(),
+            script: None,
+            record_actions: false,
+            fitness: WeightedFitness::new(config.fitness.clone()),
+            normalizer: Sensors::normalizer(width, floor_bot_y, config.obstacle.max_speed)?,
+        })
+    }
 
-        let obstacle = Obstacle {
-            pos: Vector2f::from_coords(win_width as f32, floor_bot_y - 35.0),
-            size: Vector2f::from_coords(25.0, 35.0),
-            velocity_x: -400.0,
+    /// Spawns a new obstacle at the right edge of the world.
+    fn spawn_obstacle(&mut self) {
+        let kind = match self.script.as_mut().and_then(Iterator::next) {
+            Some(kind) => kind,
+            None if self.rng.gen::<f32>() < self.obstacle_settings.bird_chance => {
+                ObstacleKind::Bird
+            }
+            None => ObstacleKind::Cactus,
         };
+        self.spawns.push(Spawn {
+            step: self.step,
+            kind,
+        });
+
+        let floor_bot_y = self.floor.bounding_box.min.y;
+        let x = self.width + self.obstacle_settings.spawn_gap;
+        self.obstacle.spawn(kind, x, floor_bot_y);
+        self.obstacle_id = self.obstacle_id.wrapping_add(1);
+    }
 
-        Self {
-            players,
-            environment: Environment { floor, obstacle },
-            generation: 0,
-        }
+    /// Replaces the obstacle with one of the given size standing on the floor
+    /// at the right edge of the world, keeping its speed. Unlike spawned
+    /// ones, it is not part of replays.
+    fn spawn_sized(&mut self, width: f32, height: f32) {
+        let floor_bot_y = self.floor.bounding_box.min.y;
+        let x = self.width + self.obstacle_settings.spawn_gap;
+        self.obstacle.spawn(ObstacleKind::Cactus, x, floor_bot_y);
+        self.obstacle.size = Vector2f::from_coords(width, height);
+        self.obstacle.pos.y = floor_bot_y - height;
+        self.obstacle_id = self.obstacle_id.wrapping_add(1);
     }
 
-    fn restart_env(&mut self, ctx: &Context) {
-        let win_width = ctx.game_window.config().width;
-        self.environment.obstacle.pos.x = win_width as f32;
-        self.environment.obstacle.velocity_x = -400.0;
+    /// Spawns a new obstacle at its initial speed.
+    fn restart(&mut self) {
+        self.step = 0;
+        self.spawns.clear();
+        self.spawn_obstacle();
+        self.obstacle.velocity_x = -self.obstacle_settings.speed;
     }
 
-    fn next_generation(&mut self) {
-        self.players
-            .sort_unstable_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    /// Stretches the floor to `width`, obstacles keep respawning at its new
+    /// right edge.
+    fn resize(&mut self, width: f32) -> Result<(), Error> {
+        self.width = width;
+        self.floor.bounding_box.max.x = width;
+        let floor_bot_y = self.floor.bounding_box.min.y;
+        self.normalizer =
+            Sensors::normalizer(width, floor_bot_y, self.obstacle_settings.max_speed)?;
 
-        let parent1_net = &self.players[0].nnet;
-        let parent2_net = &self.players[1].nnet;
-        let child_net = parent1_net.crossover(&parent2_net);
+        Ok(())
+    }
 
-        let floor_bot_y = self.environment.floor.bounding_box.min.y;
+    fn update(&mut self, step_s: f32) {
+        self.step += 1;
+        self.obstacle.update(step_s, &self.obstacle_settings);
 
-        let mut children = Vec::with_capacity(self.players.len());
-        for _ in 0..self.players.len() {
-            let mut nnet = child_net.clone();
-            nnet.mutate();
-
-            children.push(Player {
-                pos: Vector2f::from_coords(100.0, floor_bot_y - 25.0),
-                size: Vector2f::from_coords(25.0, 25.0),
-                state: MovementState::Running,
-                alive: true,
-                score: 0.0,
-                velocity: Vector2f::new(),
-                nnet,
-            });
+        if self.obstacle.is_gone() {
+            self.spawn_obstacle();
         }
+    }
 
-        self.players = children;
-        self.generation += 1;
+    fn draw(&self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        self.obstacle.draw(ctx, interpolation)?;
+        self.floor.draw(ctx)
     }
 }
 
-impl Game for DinaiGame {
-    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), String> {
-        ctx.game_window.clear(Color::RGB(240, 240, 240));
+/// Hyperparameters that can be adjusted in the tuning panel while training.
+#[derive(Debug, Clone, PartialEq)]
+struct Tuning {
+    mutation_rate: f32,
+    mutation_sigma: f32,
+    elite_count: usize,
+    population_size: usize,
+    obstacle_speed: f32,
+    obstacle_max_speed: f32,
+}
 
-        self.environment.obstacle.draw(ctx, interpolation)?;
-        for player in self.players.iter() {
-            if player.alive {
-                player.draw(ctx, interpolation)?;
+impl Tuning {
+    const COUNT: usize = 6;
+
+    /// Names of the parameters in the `set` command of the console.
+    const NAMES: [&'static str; Self::COUNT] = [
+        "mutation_rate",
+        "mutation_sigma",
+        "elite_count",
+        "population_size",
+        "obstacle_speed",
+        "obstacle_max_speed",
+    ];
+
+    /// Returns the name and value of the `i`-th parameter.
+    fn label(&self, i: usize) -> String {
+        match i {
+            0 => format!("Mutation rate: {:.3}", self.mutation_rate),
+            1 => format!("Mutation sigma: {:.2}", self.mutation_sigma),
+            2 => format!("Elite count: {}", self.elite_count),
+            3 => format!("Population size: {}", self.population_size),
+            4 => format!("Obstacle speed: {:.0}", self.obstacle_speed),
+            _ => format!("Obstacle max speed: {:.0}", self.obstacle_max_speed),
+        }
+    }
+
+    /// Increases the `i`-th parameter by `steps` steps, or decreases it if
+    /// `steps` is negative.
+    fn adjust(&mut self, i: usize, steps: i32) {
+        let steps_f = steps as f32;
+        match i {
+            0 => self.mutation_rate = (self.mutation_rate + 0.005 * steps_f).clamp(0.0, 1.0),
+            1 => self.mutation_sigma = (self.mutation_sigma + 0.05 * steps_f).max(0.0),
+            2 => {
+                // At least one child is bred in every generation.
+                let count = self.elite_count as i32 + steps;
+                self.elite_count = count.clamp(0, self.population_size as i32 - 1) as usize;
+            }
+            3 => {
+                let size = self.population_size as i32 + 50 * steps;
+                self.population_size = size.max(2) as usize;
+                self.elite_count = self.elite_count.min(self.population_size - 1);
+            }
+            4 => self.obstacle_speed = (self.obstacle_speed + 50.0 * steps_f).max(0.0),
+            _ => {
+                // The speed input of the networks needs a positive maximum.
+                self.obstacle_max_speed = (self.obstacle_max_speed + 100.0 * steps_f).max(100.0)
             }
         }
-        self.environment.floor.draw(ctx)?;
+    }
 
-        let canvas = ctx.game_window.canvas_mut();
-        let mut p_iter = self.players.iter().skip_while(|p| !p.alive);
-        if let Some(ref player) = p_iter.next() {
-            let score = format!("Score: {:.2}", player.score);
-            ctx.text_renderer.draw_text(&score, 10, 10, 0.2, canvas)?;
+    /// Sets the `i`-th parameter to `value`, limited like in [`adjust`].
+    ///
+    /// [`adjust`]: #method.adjust
+    fn set(&mut self, i: usize, value: f32) {
+        match i {
+            0 => self.mutation_rate = value,
+            1 => self.mutation_sigma = value,
+            2 => self.elite_count = value as usize,
+            3 => self.population_size = value as usize,
+            4 => self.obstacle_speed = value,
+            _ => self.obstacle_max_speed = value,
         }
+        self.adjust(i, 0);
+    }
+}
 
-        let gen = format!("Generation: {}", self.generation);
-        ctx.text_renderer.draw_text(&gen, 10, 35, 0.2, canvas)?;
+/// The best player of all generations so far.
+struct Champion {
+    generation: u32,
+    score: f32,
+    nnet: Network,
+}
 
-        let alive_cn = self
-            .players
-            .iter()
-            .fold(0, |acc, p| if p.alive { acc + 1 } else { acc });
-        let alive = format!("Alive: {}", alive_cn);
-        ctx.text_renderer.draw_text(&alive, 10, 60, 0.2, canvas)?;
+struct DinaiGame {
+    population: Population<Player>,
+    seed: u64,
+    rng: StdRng,
+    last_best_score: f32,
+    history: StatsHistory,
+    stats_recorder: Option<StatsRecorder<BufWriter<File>>>,
+    curriculum: Curriculum,
+
+    // The best player of every generation is saved as a replay into this
+    // directory if set.
+    replay_dir: Option<PathBuf>,
+    config: Config,
+
+    // Reloads the config file given on the command line between
+    // generations, and the speed multiplier of the last reload waiting for
+    // the next update of the window.
+    config_watcher: Option<ConfigWatcher>,
+    pending_speed: Option<f32>,
+
+    // The tuning panel toggled by F1, its selected parameter and the
+    // changes waiting for the next generation.
+    show_tuning: bool,
+    tuning_selected: usize,
+    pending_tuning: Option<Tuning>,
+
+    // The developer console toggled by the backtick key.
+    console: Console<DinaiGame>,
+
+    // Breeds the next generation while the window keeps drawing the last
+    // one, enabled only when running in a window.
+    breeding: Option<JoinHandle<(Population<Player>, StdRng)>>,
+    breed_in_background: bool,
+
+    // Kept even if later generations regress, and saved as JSON into
+    // `champion_path` whenever it improves if set.
+    champion: Option<Champion>,
+    champion_path: Option<PathBuf>,
+
+    // Index of the best player of the previous generation, which is the
+    // first elite carried over unchanged.
+    previous_best: Option<usize>,
+
+    // Simulated time in seconds since the current generation started.
+    generation_time: f32,
+
+    environment: Environment,
 
-        let speed = format!("Speed: {:.1}", ctx.speed);
-        ctx.text_renderer.draw_text(&speed, 10, 110, 0.2, canvas)?;
+    #[cfg(feature = "metrics")]
+    metrics: Arc<Metrics>,
+}
+
+impl DinaiGame {
+    /// Creates a new game with the first generation drawn from `seed`. Runs
+    /// with equal seeds produce identical generations.
+    fn new(config: &Config, seed: u64) -> Result<Self, Error> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let environment = Environment::new(config, rng.gen())?;
+        let floor_bot_y = environment.floor.bounding_box.min.y;
+
+        let mut players = Vec::new();
+        for _ in 0..config.simulation.population_size {
+            players.push(Player::new(floor_bot_y, Network::new(&mut rng)));
+        }
+
+        let mut population = Population::new(players);
+        population.set_elite_count(config.genetic.elite_count);
+        population.set_species_threshold(config.genetic.species_threshold);
+        population.set_mutation(MutationConfig {
+            rate: config.genetic.mutation_rate,
+            clamp: config.genetic.weight_clamp,
+            weight_decay: config.genetic.weight_decay,
+            ..Default::default()
+        });
+
+        let mut game = Self {
+            population,
+            environment,
+            seed,
+            rng,
+            last_best_score: 0.0,
+            history: StatsHistory::new(),
+            stats_recorder: None,
+            curriculum: Curriculum::new(config.obstacle.clone(), config.curriculum.clone()),
+            replay_dir: None,
+            config: config.clone(),
+            config_watcher: None,
+            pending_speed: None,
+            show_tuning: false,
+            tuning_selected: 0,
+            pending_tuning: None,
+            console: Self::console(),
+            breeding: None,
+            breed_in_background: false,
+            champion: None,
+            champion_path: None,
+            previous_best: None,
+            generation_time: 0.0,
+            #[cfg(feature = "metrics")]
+            metrics: Arc::new(Metrics::new()),
+        };
+        game.restart_env();
 
-        ctx.game_window.present();
+        Ok(game)
+    }
+
+    /// Replaces the population with the networks of `checkpoint` and
+    /// continues training from the generation following it. Fails if the
+    /// checkpoint has no networks.
+    fn resume(&mut self, checkpoint: Checkpoint<Network>) -> Result<(), Error> {
+        if checkpoint.genomes.is_empty() {
+            return Err(Error::Config(format!(
+                "checkpoint of generation {} has no networks",
+                checkpoint.generation
+            )));
+        }
+
+        self.last_best_score = checkpoint.best_score;
+        self.champion = checkpoint.genomes.first().map(|nnet| Champion {
+            generation: checkpoint.generation,
+            score: checkpoint.best_score,
+            nnet: nnet.clone(),
+        });
+        self.seed_population(&checkpoint.genomes, checkpoint.generation + 1);
 
         Ok(())
     }
 
-    fn handle_input(&mut self, ctx: &mut Context) -> Result<(), String> {
-        if ctx.game_window.is_key_pressed(&Keycode::Q) {
-            ctx.game_window.close();
+    /// Replaces the population with the given networks, which are kept as
+    /// they are, and their mutated copies filling the rest of it. `networks`
+    /// must not be empty.
+    fn seed_population(&mut self, networks: &[Network], generation: u32) {
+        let floor_bot_y = self.environment.floor.bounding_box.min.y;
+        let mutation = self.population.mutation().at_generation(generation);
+
+        for (i, player) in self.population.genomes_mut().iter_mut().enumerate() {
+            let mut nnet = networks[i % networks.len()].clone();
+            if i >= networks.len() {
+                nnet.mutate(&mutation, &mut self.rng);
+            }
+
+            *player = Player::new(floor_bot_y, nnet);
         }
 
-        if ctx.game_window.is_key_pressed(&Keycode::K) {
-            ctx.speed += 0.3 * ctx.step_s;
+        self.population.set_generation(generation);
+        self.restart_env();
+    }
+
+    /// Restarts the environment with the obstacle parameters the curriculum
+    /// chooses for the current generation.
+    fn restart_env(&mut self) {
+        let generation = self.population.generation();
+        self.environment.obstacle_settings = self
+            .curriculum
+            .obstacle_settings(generation, self.last_best_score);
+        self.environment.restart();
+    }
+
+    fn save_checkpoint(&self) -> Result<(), Error> {
+        let checkpoint = Checkpoint {
+            generation: self.population.generation(),
+            best_score: self.last_best_score,
+            genomes: self
+                .population
+                .genomes()
+                .iter()
+                .take(CHECKPOINT_TOP)
+                .map(|p| p.nnet.clone())
+                .collect(),
+        };
+
+        let path = checkpoint.save(CHECKPOINT_DIR)?;
+        info!("Saved checkpoint {}", path.display());
+
+        Ok(())
+    }
+
+    /// Returns the hyperparameters the tuning panel shows, which are the
+    /// pending ones if any were changed.
+    fn tuning(&self) -> Tuning {
+        if let Some(tuning) = &self.pending_tuning {
+            return tuning.clone();
         }
-        if ctx.game_window.is_key_pressed(&Keycode::J) {
-            ctx.speed -= 0.3 * ctx.step_s;
-            ctx.speed = ctx.speed.max(0.1);
+
+        let mutation = self.population.mutation();
+        Tuning {
+            mutation_rate: mutation.rate,
+            mutation_sigma: mutation.sigma,
+            elite_count: self.population.elite_count(),
+            population_size: self.population.size(),
+            obstacle_speed: self.config.obstacle.speed,
+            obstacle_max_speed: self.config.obstacle.max_speed,
         }
+    }
+
+    /// Applies the hyperparameters changed in the tuning panel. Called at
+    /// the boundary of two generations.
+    fn apply_tuning(&mut self) {
+        let tuning = match self.pending_tuning.take() {
+            Some(tuning) => tuning,
+            None => return,
+        };
+
+        self.population.set_mutation(MutationConfig {
+            rate: tuning.mutation_rate,
+            sigma: tuning.mutation_sigma,
+            ..*self.population.mutation()
+        });
+        self.population.set_elite_count(tuning.elite_count);
+        self.config.genetic.elite_count = tuning.elite_count;
+        self.population.set_size(tuning.population_size);
+        self.config.simulation.population_size = tuning.population_size;
+
+        self.config.obstacle.speed = tuning.obstacle_speed;
+        self.config.obstacle.max_speed = tuning.obstacle_max_speed;
+        self.curriculum =
+            Curriculum::new(self.config.obstacle.clone(), self.config.curriculum.clone());
+    }
+
+    /// Creates the console with the commands of the training.
+    fn console() -> Console<Self> {
+        let mut console = Console::new();
+        console.register("set", "set <parameter> <value>", Self::command_set);
+        console.register("save", "save <path>", Self::command_save);
+        console.register(
+            "spawn",
+            "spawn obstacle <width> <height>",
+            Self::command_spawn,
+        );
+
+        console
+    }
+
+    /// Sets a parameter of the tuning panel, which is applied with the next
+    /// generation like the panel does.
+    fn command_set(&mut self, args: &[&str]) -> Result<String, Error> {
+        let (name, value) = match args {
+            [name, value] => (*name, *value),
+            _ => return Err(Error::Config("usage: set <parameter> <value>".to_string())),
+        };
+        let i = Tuning::NAMES
+            .iter()
+            .position(|&other| other == name)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "unknown parameter {}, expected one of {}",
+                    name,
+                    Tuning::NAMES.join(", ")
+                ))
+            })?;
+        let value = value
+            .parse::<f32>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| Error::Config(format!("invalid value {}", value)))?;
+
+        let mut tuning = self.tuning();
+        tuning.set(i, value);
+        let label = tuning.label(i);
+        self.pending_tuning = Some(tuning);
+
+        Ok(format!("{} from the next generation", label))
+    }
+
+    /// Saves the network of the champion as JSON.
+    fn command_save(&mut self, args: &[&str]) -> Result<String, Error> {
+        let path = match args {
+            [path] => Path::new(path),
+            _ => return Err(Error::Config("usage: save <path>".to_string())),
+        };
+        let champion = self
+            .champion
+            .as_ref()
+            .ok_or_else(|| Error::Config("there is no champion yet".to_string()))?;
+        save_network(path, &champion.nnet)?;
+
+        Ok(format!(
+            "Saved the champion of generation {} into {}",
+            champion.generation,
+            path.display()
+        ))
+    }
+
+    /// Replaces the obstacle with one of the given size.
+    fn command_spawn(&mut self, args: &[&str]) -> Result<String, Error> {
+        let usage = || Error::Config("usage: spawn obstacle <width> <height>".to_string());
+        let (width, height) = match args {
+            ["obstacle", width, height] => (width.parse::<f32>(), height.parse::<f32>()),
+            _ => return Err(usage()),
+        };
+        let (width, height) = width
+            .ok()
+            .zip(height.ok())
+            .filter(|&(width, height)| {
+                width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()
+            })
+            .ok_or_else(|| Error::Config("the size must be positive".to_string()))?;
+        self.environment.spawn_sized(width, height);
+
+        Ok(format!("Spawned a {}x{} obstacle", width, height))
+    }
+
+    /// Applies the changes of the watched config file that are safe while
+    /// training: the mutation rate, the obstacle speed ramp and the speed
+    /// multiplier. Other changes take effect on the next start. Called at
+    /// the boundary of two generations, after the tuning panel.
+    fn reload_config(&mut self) {
+        let watcher = match &mut self.config_watcher {
+            Some(watcher) => watcher,
+            None => return,
+        };
+        let config = match watcher.poll() {
+            Some(Ok(config)) => config,
+            Some(Err(err)) => {
+                warn!("Not reloading {}: {}", watcher.path().display(), err);
+                return;
+            }
+            None => return,
+        };
+        info!("Reloaded {}", watcher.path().display());
+
+        self.population.set_mutation(MutationConfig {
+            rate: config.genetic.mutation_rate,
+            ..*self.population.mutation()
+        });
+        self.config.genetic.mutation_rate = config.genetic.mutation_rate;
+
+        self.config.obstacle.speed = config.obstacle.speed;
+        self.config.obstacle.acceleration = config.obstacle.acceleration;
+        self.config.obstacle.max_speed = config.obstacle.max_speed;
+        self.curriculum =
+            Curriculum::new(self.config.obstacle.clone(), self.config.curriculum.clone());
+
+        self.config.simulation.speed = config.simulation.speed;
+        self.pending_speed = Some(config.simulation.speed);
+    }
+
+    /// Saves a replay of the best player of every generation into `dir`.
+    fn save_replays(&mut self, dir: PathBuf) {
+        self.replay_dir = Some(dir);
+        self.environment.record_actions = true;
+    }
+
+    fn save_replay(&self, dir: &Path) -> Result<(), Error> {
+        let env = &self.environment;
+        let mut config = self.config.clone();
+        config.window.width = env.width as u32;
+        config.obstacle = env.obstacle_settings.clone();
+
+        let best = &self.population.genomes()[0];
+        let replay = Replay {
+            seed: self.seed,
+            generation: self.population.generation(),
+            score: best.score,
+            config,
+            spawns: env.spawns.clone(),
+            actions: best.actions.clone(),
+        };
+        let path = replay.save(dir)?;
+        debug!("Saved replay {}", path.display());
 
         Ok(())
     }
 
-    fn update(&mut self, ctx: &mut Context) -> Result<(), String> {
-        let env = &mut self.environment;
-        let step_s = ctx.step_s;
+    fn next_generation(&mut self) -> Result<(), Error> {
+        self.end_generation()?;
+        self.population.evolve(&mut self.rng);
+        self.start_generation();
 
-        self.players
-            .par_iter_mut()
-            .filter(|player| player.alive)
-            .for_each(|player| {
-                player.update(step_s, env);
-            });
+        Ok(())
+    }
 
-        let any_alive = self.players.par_iter().any(|player| player.alive);
+    /// Breeds the next generation on another thread. The window keeps
+    /// drawing while [`finish_breeding`] waits for it.
+    ///
+    /// [`finish_breeding`]: #method.finish_breeding
+    fn start_breeding(&mut self) -> Result<(), Error> {
+        self.end_generation()?;
+
+        // The population stays in place, so that the window can still show
+        // it. Cloning is cheap compared to breeding.
+        let mut population = self.population.clone();
+        let mut rng = self.rng.clone();
+        self.breeding = Some(thread::spawn(move || {
+            population.evolve(&mut rng);
+            (population, rng)
+        }));
 
-        if any_alive {
-            env.obstacle.update(ctx);
-        } else {
-            self.next_generation();
-            self.restart_env(ctx);
+        Ok(())
+    }
+
+    /// Starts the generation bred by [`start_breeding`] once it is ready.
+    /// Returns false while the breeding is still running.
+    ///
+    /// [`start_breeding`]: #method.start_breeding
+    fn finish_breeding(&mut self) -> bool {
+        match self.breeding.take() {
+            Some(breeding) if breeding.is_finished() => {
+                let (population, rng) = breeding
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err));
+                self.population = population;
+                self.rng = rng;
+                self.start_generation();
+
+                true
+            }
+            Some(breeding) => {
+                self.breeding = Some(breeding);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Makes the best player of the current generation, which must be sorted
+    /// by fitness, the champion and saves it if requested. Failing to save
+    /// it does not stop the training, the next champion is saved again.
+    fn update_champion(&mut self) {
+        let best = &self.population.genomes()[0];
+        let champion = self.champion.insert(Champion {
+            generation: self.population.generation(),
+            score: best.score,
+            nnet: best.nnet.clone(),
+        });
+
+        if let Some(path) = &self.champion_path {
+            match save_network(path, &champion.nnet) {
+                Ok(()) => debug!(
+                    "Saved champion of generation {} into {}",
+                    champion.generation,
+                    path.display()
+                ),
+                Err(err) => warn!(
+                    "Failed to save champion of generation {} into {}: {}",
+                    champion.generation,
+                    path.display(),
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Evaluates the finished generation and applies pending tuning and
+    /// config changes, so that the population is ready to evolve.
+    fn end_generation(&mut self) -> Result<(), Error> {
+        self.population.sort_by_fitness();
+        self.last_best_score = self.population.genomes()[0].score;
+
+        if self
+            .champion
+            .as_ref()
+            .is_none_or(|champion| self.last_best_score > champion.score)
+        {
+            self.update_champion();
+        }
+
+        if let Some(dir) = &self.replay_dir {
+            self.save_replay(dir)?;
+        }
+
+        let fitness: Vec<f32> = self.population.genomes().iter().map(|p| p.score).collect();
+        if let Some(stats) = GenerationStats::from_fitness(self.population.generation(), &fitness) {
+            info!(
+                "Generation {}: best score {:.2}, mean {:.2}, median {:.2}",
+                stats.generation, stats.best, stats.mean, stats.median
+            );
+
+            if let Some(recorder) = &mut self.stats_recorder {
+                let mutation = self
+                    .population
+                    .mutation()
+                    .at_generation(self.population.generation());
+                recorder.record(&stats, self.generation_time, &mutation)?;
+            }
+
+            self.history.record(stats);
+        }
+        self.generation_time = 0.0;
+
+        let generation = self.population.generation();
+        if generation > 0 && generation.is_multiple_of(CHECKPOINT_INTERVAL) {
+            self.save_checkpoint()?;
         }
 
+        self.apply_tuning();
+        self.reload_config();
+
         Ok(())
     }
-}
 
-fn main() -> Result<(), String> {
-    let win_conf = WindowConfig {
-        title: "dinai",
-        width: 1280,
-        height: 720,
-    };
+    /// Puts the players of a freshly evolved generation to the start.
+    fn start_generation(&mut self) {
+        self.previous_best = (self.population.elite_count() > 0).then_some(0);
+
+        let floor_bot_y = self.environment.floor.bounding_box.min.y;
+        for player in self.population.genomes_mut() {
+            player.reset(floor_bot_y);
+        }
 
-    let mut game_window = GameWindow::new(win_conf)?;
+        #[cfg(feature = "metrics")]
+        self.metrics.set_best_fitness(self.last_best_score);
 
-    let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
-    let text_renderer = TextRenderer::new(&ttf_context, game_window.canvas())?;
+        #[cfg(feature = "metrics")]
+        self.metrics
+            .set_generation(self.population.generation().into());
 
-    let mut ctx = Context {
-        game_window: &mut game_window,
-        text_renderer: &text_renderer,
-        step_s: 1.0 / 30.0,
-        speed: 1.0,
-    };
+        self.restart_env();
+    }
+}
+
+impl Scene for DinaiGame {
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        ctx.game_window.clear(Color::RGB(240, 240, 240));
+
+        let leaders = self.leaders();
+        let previous_best = self.previous_best;
+        for (i, player) in self.population.genomes_mut().iter_mut().enumerate() {
+            player.color = match leaders.iter().position(|&leader| leader == i) {
+                Some(rank) => LEADER_COLORS[rank],
+                None if previous_best == Some(i) => CHAMPION_COLOR,
+                None => CROWD_COLOR,
+            };
+        }
+
+        // Highlighted players are drawn last, so that they stay visible in
+        // the crowd.
+        let highlighted: Vec<usize> = previous_best
+            .into_iter()
+            .chain(leaders.iter().rev().copied())
+            .collect();
+        let genomes = self.population.genomes();
+        for (i, player) in genomes.iter().enumerate() {
+            if player.alive && !highlighted.contains(&i) {
+                player.draw(ctx, interpolation)?;
+            }
+        }
+        for &i in highlighted.iter() {
+            if genomes[i].alive {
+                genomes[i].draw(ctx, interpolation)?;
+            }
+        }
+        self.environment.draw(ctx, interpolation)?;
 
-    let mut the_game = DinaiGame::new(&mut ctx);
+        let canvas = ctx.game_window.canvas_mut();
+        if let Some(&leader) = leaders.first() {
+            let score = format!("Score: {:.2}", self.population.genomes()[leader].score);
+            ctx.text_renderer.draw_text(&score, 10, 10, 0.2, canvas)?;
+        }
 
-    let mut start_time = Instant::now();
-    let mut lag = 0.0;
+        let gen = format!("Generation: {}", self.population.generation());
+        ctx.text_renderer.draw_text(&gen, 10, 35, 0.2, canvas)?;
 
-    while !ctx.game_window.should_close() {
-        let delta_time = start_time.elapsed().as_secs_f32() * ctx.speed;
-        start_time = Instant::now();
-        lag += delta_time.min(0.3);
+        let alive_cn =
+            self.population
+                .genomes()
+                .iter()
+                .fold(0, |acc, p| if p.alive { acc + 1 } else { acc });
+        let alive = if self.breeding.is_some() {
+            "Breeding...".to_string()
+        } else {
+            format!("Alive: {}", alive_cn)
+        };
+        ctx.text_renderer.draw_text(&alive, 10, 60, 0.2, canvas)?;
 
-        ctx.game_window.poll();
-        the_game.handle_input(&mut ctx)?;
+        if let Some(champion) = &self.champion {
+            let best = format!(
+                "All-time best: {:.2} (generation {})",
+                champion.score, champion.generation
+            );
+            ctx.text_renderer.draw_text(&best, 10, 85, 0.2, canvas)?;
+        }
 
-        while lag > ctx.step_s {
-            the_game.update(&mut ctx)?;
-            lag -= ctx.step_s;
+        draw_status(ctx)?;
+        self.draw_leaderboard(ctx, &leaders)?;
+        self.draw_history(ctx)?;
+        if self.show_tuning {
+            self.draw_tuning(ctx)?;
+        }
+        if self.console.is_open() {
+            self.draw_console(ctx)?;
         }
 
-        the_game.draw(&mut ctx, lag)?;
+        Ok(())
     }
 
-    Ok(())
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        // The console takes all keys while it is open.
+        if self.console.is_open() {
+            return self.handle_console_input(ctx);
+        }
+        if ctx.input.was_action_just_pressed(Action::ToggleConsole) {
+            self.console.set_open(true);
+            ctx.game_window.start_text_input();
+            return Ok(Transition::Stay);
+        }
+
+        // F1 toggles the tuning panel, the arrows select and adjust its
+        // parameters.
+        let input = &ctx.input;
+        if input.was_action_just_pressed(Action::ToggleTuning) {
+            self.show_tuning = !self.show_tuning;
+        }
+        if self.show_tuning {
+            if input.was_action_just_pressed(Action::Up) {
+                self.tuning_selected = (self.tuning_selected + Tuning::COUNT - 1) % Tuning::COUNT;
+            }
+            if input.was_action_just_pressed(Action::Down) {
+                self.tuning_selected = (self.tuning_selected + 1) % Tuning::COUNT;
+            }
+
+            let steps = input.was_action_just_pressed(Action::Increase) as i32
+                - input.was_action_just_pressed(Action::Decrease) as i32;
+            if steps != 0 {
+                let mut tuning = self.tuning();
+                tuning.adjust(self.tuning_selected, steps);
+                self.pending_tuning = Some(tuning);
+            }
+        }
+
+        // F skips the next generations without drawing them.
+        if ctx.input.was_action_just_pressed(Action::FastForward) {
+            ctx.state = SimulationState::FastForward(FastForward {
+                until_generation: self.population.generation() + FAST_FORWARD_GENERATIONS,
+                target_score: None,
+            });
+        }
+
+        handle_common_input(ctx)
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        if let Some(speed) = self.pending_speed.take() {
+            ctx.speed = speed;
+        }
+
+        if !ctx
+            .profiler
+            .time(Phase::Breeding, || self.finish_breeding())
+        {
+            return Ok(Transition::Stay);
+        }
+
+        self.step(ctx.step_s, &mut ctx.profiler)?;
+
+        Ok(Transition::Stay)
+    }
+
+    fn on_resize(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        let width = ctx.game_window.logical_size().0 as f32;
+        self.environment.resize(width)
+    }
+
+    fn progress(&self) -> (u32, f32) {
+        (self.population.generation(), self.last_best_score)
+    }
+}
+
+impl DinaiGame {
+    /// Draws the best and mean score of recent generations into the upper
+    /// right corner.
+    fn draw_history(&self, ctx: &mut Context) -> Result<(), Error> {
+        const WIDTH: i32 = 300;
+        const HEIGHT: i32 = 120;
+
+        let history = self.history.last(PLOT_GENERATIONS);
+        let max_score = history.iter().map(|s| s.best).fold(0.0, f32::max);
+
+        let left = ctx.game_window.logical_size().0 as i32 - WIDTH - 10;
+        let top = 10;
+        let canvas = ctx.game_window.canvas_mut();
+
+        canvas.set_draw_color(Color::RGB(200, 200, 200));
+        canvas.draw_rect(Rect::new(left, top, WIDTH as u32, HEIGHT as u32))?;
+
+        if history.len() < 2 || max_score <= 0.0 {
+            return Ok(());
+        }
+
+        let to_point = |i: usize, score: f32| {
+            let x = left + (i as i32 * WIDTH) / (history.len() as i32 - 1);
+            let y = top + HEIGHT - ((score / max_score) * HEIGHT as f32) as i32;
+            Point::new(x, y)
+        };
+
+        let best: Vec<Point> = history
+            .iter()
+            .enumerate()
+            .map(|(i, s)| to_point(i, s.best))
+            .collect();
+        let mean: Vec<Point> = history
+            .iter()
+            .enumerate()
+            .map(|(i, s)| to_point(i, s.mean))
+            .collect();
+
+        canvas.set_draw_color(Color::RGB(200, 0, 0));
+        canvas.draw_lines(&best[..])?;
+        canvas.set_draw_color(Color::RGB(0, 0, 200));
+        canvas.draw_lines(&mean[..])?;
+
+        let label = format!("Best: {:.2}", max_score);
+        let style = TextStyle {
+            scale: 0.15,
+            align: TextAlign::Right,
+            ..Default::default()
+        };
+        ctx.text_renderer
+            .draw_text_styled(&label, left + WIDTH - 5, top + 5, &style, canvas)?;
+
+        Ok(())
+    }
+
+    /// Returns the indices of the alive players with the highest scores,
+    /// from the best one.
+    fn leaders(&self) -> Vec<usize> {
+        let genomes = self.population.genomes();
+        let mut alive: Vec<usize> = (0..genomes.len()).filter(|&i| genomes[i].alive).collect();
+        alive.sort_by(|&a, &b| genomes[b].score.total_cmp(&genomes[a].score));
+        alive.truncate(LEADER_COLORS.len());
+
+        alive
+    }
+
+    /// Draws the scores of the `leaders` in their colors under the status.
+    fn draw_leaderboard(&self, ctx: &mut Context, leaders: &[usize]) -> Result<(), Error> {
+        let canvas = ctx.game_window.canvas_mut();
+
+        for (rank, (&i, &color)) in leaders.iter().zip(LEADER_COLORS.iter()).enumerate() {
+            let line = format!("{}. {:.2}", rank + 1, self.population.genomes()[i].score);
+            let style = TextStyle {
+                scale: 0.15,
+                color,
+                ..Default::default()
+            };
+            let y = 170 + rank as i32 * 20;
+            ctx.text_renderer
+                .draw_text_styled(&line, 10, y, &style, canvas)?;
+        }
+
+        if self.previous_best.is_some() {
+            let style = TextStyle {
+                scale: 0.15,
+                color: CHAMPION_COLOR,
+                ..Default::default()
+            };
+            let y = 170 + LEADER_COLORS.len() as i32 * 20;
+            ctx.text_renderer
+                .draw_text_styled("Previous champion", 10, y, &style, canvas)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws the tuning panel under the score history.
+    fn draw_tuning(&self, ctx: &mut Context) -> Result<(), Error> {
+        let left = ctx.game_window.logical_size().0 as i32 - 310;
+        let canvas = ctx.game_window.canvas_mut();
+        let tuning = self.tuning();
+
+        let mut title = format!(
+            "Tuning ({}/{} adjust)",
+            ctx.input.key(Action::Decrease).name(),
+            ctx.input.key(Action::Increase).name()
+        );
+        if self.pending_tuning.is_some() {
+            title.push_str(", next generation");
+        }
+        ctx.text_renderer
+            .draw_text(&title, left, 140, 0.15, canvas)?;
+
+        for i in 0..Tuning::COUNT {
+            let marker = if i == self.tuning_selected {
+                "> "
+            } else {
+                "  "
+            };
+            let line = format!("{}{}", marker, tuning.label(i));
+            let y = 160 + i as i32 * 20;
+            ctx.text_renderer.draw_text(&line, left, y, 0.15, canvas)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles the input while the console is open. Enter runs the typed
+    /// command, Escape or the backtick key closes the console.
+    fn handle_console_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        let input = &ctx.input;
+        let window = &mut ctx.game_window;
+        if input.was_action_just_pressed(Action::Back)
+            || input.was_action_just_pressed(Action::ToggleConsole)
+        {
+            self.console.set_open(false);
+            window.stop_text_input();
+        } else if input.was_action_just_pressed(Action::Confirm) {
+            let line = window.text_input().to_string();
+            // The commands change the game, which owns the console.
+            let mut console = mem::take(&mut self.console);
+            console.execute(self, &line);
+            self.console = console;
+            window.start_text_input();
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    /// Draws the last lines of the console and the command being typed over
+    /// the bottom of the window.
+    fn draw_console(&self, ctx: &mut Context) -> Result<(), Error> {
+        const LINES: usize = 8;
+        const LINE_HEIGHT: i32 = 20;
+
+        let (width, height) = ctx.game_window.logical_size();
+        let panel_height = (LINES as u32 + 1) * LINE_HEIGHT as u32 + 10;
+        let top = height.saturating_sub(panel_height) as i32;
+        let prompt = format!("> {}_", ctx.game_window.text_input());
+        let canvas = ctx.game_window.canvas_mut();
+
+        // The simulation shows through the panel.
+        let blend_mode = canvas.blend_mode();
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(255, 255, 255, 220));
+        canvas.fill_rect(Rect::new(0, top, width, panel_height))?;
+        canvas.set_blend_mode(blend_mode);
+
+        let lines = self.console.lines();
+        let shown = &lines[lines.len().saturating_sub(LINES)..];
+        for (i, line) in shown.iter().chain(Some(&prompt)).enumerate() {
+            let y = top + 5 + i as i32 * LINE_HEIGHT;
+            ctx.text_renderer.draw_text(line, 10, y, 0.15, canvas)?;
+        }
+
+        Ok(())
+    }
+
+    /// Advances the simulation by one fixed step. Does not depend on the
+    /// window so that it can also be driven by the headless trainer.
+    ///
+    /// All players think before any of them moves, so that both phases can
+    /// be timed by the `profiler`.
+    fn step(&mut self, step_s: f32, profiler: &mut Profiler) -> Result<(), Error> {
+        let env = &mut self.environment;
+        let genomes = self.population.genomes_mut();
+
+        profiler.time(Phase::Think, || {
+            genomes
+                .par_iter_mut()
+                .filter(|player| player.alive)
+                .for_each(|player| player.decide(step_s, env));
+        });
+        profiler.time(Phase::Physics, || {
+            genomes
+                .par_iter_mut()
+                .filter(|player| player.alive)
+                .for_each(|player| player.act(player.controls, step_s, env));
+        });
+
+        #[cfg(feature = "metrics")]
+        self.metrics.add_steps(1);
+
+        self.generation_time += step_s;
+
+        let any_alive = self
+            .population
+            .genomes()
+            .par_iter()
+            .any(|player| player.alive);
+
+        if any_alive {
+            profiler.time(Phase::Physics, || env.update(step_s));
+        } else if self.breed_in_background {
+            profiler.time(Phase::Breeding, || self.start_breeding())?;
+        } else {
+            profiler.time(Phase::Breeding, || self.next_generation())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Entries of the start menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum MenuItem {
+    Train,
+    Play,
+    LoadNetwork,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 4] = [
+        MenuItem::Train,
+        MenuItem::Play,
+        MenuItem::LoadNetwork,
+        MenuItem::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Train => "Train",
+            MenuItem::Play => "Play latest checkpoint",
+            MenuItem::LoadNetwork => "Load network",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// The start menu, shown when dinai is started without choosing a mode.
+/// Escape returns to it from the chosen mode.
+struct MenuScene {
+    // Options of the command line applied to the chosen training.
+    args: Args,
+    config: Config,
+    selected: usize,
+
+    // Whether the path of a network is being typed.
+    entering_path: bool,
+
+    // Why the last choice failed.
+    message: Option<String>,
+
+    // Shared by all trainings, so that the server is started only once.
+    #[cfg(feature = "metrics")]
+    metrics: Arc<Metrics>,
+}
+
+impl MenuScene {
+    fn new(args: &Args, config: &Config) -> Self {
+        Self {
+            args: args.clone(),
+            config: config.clone(),
+            selected: 0,
+            entering_path: false,
+            message: None,
+            #[cfg(feature = "metrics")]
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Starts the mode of `item`.
+    fn choose(&mut self, item: MenuItem, ctx: &mut Context) -> Result<Transition, Error> {
+        match item {
+            MenuItem::Train => {
+                let seed = self.args.seed.unwrap_or_else(rand::random);
+                info!("Seed: {}", seed);
+
+                let mut the_game = new_training(&self.args, &self.config, seed)?;
+                the_game.breed_in_background = true;
+                #[cfg(feature = "metrics")]
+                {
+                    the_game.metrics = self.metrics.clone();
+                }
+
+                if let Some(target) = self
+                    .args
+                    .fast_forward_target(the_game.population.generation())
+                {
+                    ctx.state = SimulationState::FastForward(target);
+                }
+
+                Ok(Transition::Push(Box::new(the_game)))
+            }
+            MenuItem::Play => {
+                let path = latest_checkpoint()?
+                    .ok_or_else(|| Error::Config(format!("no checkpoint in {}", CHECKPOINT_DIR)))?;
+                let checkpoint: Checkpoint<Network> = Checkpoint::load(&path)?;
+                let nnet =
+                    checkpoint.genomes.into_iter().next().ok_or_else(|| {
+                        Error::Config(format!("empty checkpoint {}", path.display()))
+                    })?;
+
+                Ok(Transition::Push(Box::new(PlayGame::new(
+                    &self.config,
+                    nnet,
+                )?)))
+            }
+            MenuItem::LoadNetwork => {
+                self.entering_path = true;
+                ctx.game_window.start_text_input();
+
+                Ok(Transition::Stay)
+            }
+            MenuItem::Quit => Ok(Transition::Quit),
+        }
+    }
+
+    /// Handles the input while the path of a network is typed. Enter plays
+    /// the network, Escape goes back to the menu.
+    fn handle_path_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        let window = &mut ctx.game_window;
+        if ctx.input.was_action_just_pressed(Action::Back) {
+            self.entering_path = false;
+            window.stop_text_input();
+        } else if ctx.input.was_action_just_pressed(Action::Confirm) {
+            self.entering_path = false;
+            window.stop_text_input();
+
+            let nnet = load_network(window.text_input())?;
+            return Ok(Transition::Push(Box::new(PlayGame::new(
+                &self.config,
+                nnet,
+            )?)));
+        }
+
+        Ok(Transition::Stay)
+    }
+}
+
+impl Scene for MenuScene {
+    fn draw(&mut self, ctx: &mut Context, _interpolation: f32) -> Result<(), Error> {
+        const TOP: i32 = 250;
+        const LINE_HEIGHT: i32 = 45;
+
+        ctx.game_window.clear(Color::RGB(240, 240, 240));
+
+        let center = ctx.game_window.logical_size().0 as i32 / 2;
+        let key = |action| ctx.input.key(action).name();
+        let hint = if self.entering_path {
+            format!(
+                "Network: {}_ ({} loads, {} cancels)",
+                ctx.game_window.text_input(),
+                key(Action::Confirm),
+                key(Action::Back)
+            )
+        } else {
+            format!(
+                "{}/{} select, {} chooses, {} shows all keys",
+                key(Action::Up),
+                key(Action::Down),
+                key(Action::Confirm),
+                key(Action::ToggleHelp)
+            )
+        };
+
+        let canvas = ctx.game_window.canvas_mut();
+        let style = TextStyle {
+            scale: 0.6,
+            align: TextAlign::Center,
+            ..Default::default()
+        };
+        ctx.text_renderer
+            .draw_text_styled("dinai", center, 100, &style, canvas)?;
+
+        let style = TextStyle {
+            scale: 0.25,
+            ..style
+        };
+        for (i, item) in MenuItem::ALL.iter().enumerate() {
+            let line = if i == self.selected {
+                format!("> {} <", item.label())
+            } else {
+                item.label().to_string()
+            };
+            let y = TOP + i as i32 * LINE_HEIGHT;
+            ctx.text_renderer
+                .draw_text_styled(&line, center, y, &style, canvas)?;
+        }
+
+        let style = TextStyle {
+            scale: 0.15,
+            ..style
+        };
+        let y = TOP + MenuItem::ALL.len() as i32 * LINE_HEIGHT + 20;
+        ctx.text_renderer
+            .draw_text_styled(&hint, center, y, &style, canvas)?;
+
+        if let Some(message) = &self.message {
+            let style = TextStyle {
+                color: Color::RGB(200, 0, 0),
+                ..style
+            };
+            ctx.text_renderer
+                .draw_text_styled(message, center, y + 30, &style, canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        // Nothing is simulated in the menu, so a mode left while paused or
+        // fast-forwarding does not keep the loop in that state.
+        ctx.state = SimulationState::Running;
+
+        let res = if self.entering_path {
+            self.handle_path_input(ctx)
+        } else if ctx.input.was_action_just_pressed(Action::Confirm) {
+            self.choose(MenuItem::ALL[self.selected], ctx)
+        } else {
+            let count = MenuItem::ALL.len();
+            if ctx.input.was_action_just_pressed(Action::Up) {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            if ctx.input.was_action_just_pressed(Action::Down) {
+                self.selected = (self.selected + 1) % count;
+            }
+
+            handle_common_input(ctx)
+        };
+
+        match res {
+            Ok(transition) => {
+                if !matches!(transition, Transition::Stay) {
+                    self.message = None;
+                }
+                Ok(transition)
+            }
+            // The window cannot be used after some errors, e.g. failing to toggle vsync.
+            Err(err) if ctx.game_window.should_close() => Err(err),
+            Err(err) => {
+                self.message = Some(err.to_string());
+                Ok(Transition::Stay)
+            }
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut Context) -> Result<Transition, Error> {
+        Ok(Transition::Stay)
+    }
+}
+
+/// Returns the checkpoint of the latest generation in the checkpoint
+/// directory, if there is any.
+fn latest_checkpoint() -> Result<Option<PathBuf>, Error> {
+    let entries = match fs::read_dir(CHECKPOINT_DIR) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    // Checkpoints are named after their zero-padded generation.
+    let mut latest = None;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            latest = latest.max(Some(path));
+        }
+    }
+
+    Ok(latest)
+}
+
+/// A single player controlled by a trained network, restarting whenever it
+/// dies. Nothing is evolved.
+struct PlayGame {
+    player: Player,
+    environment: Environment,
+    attempts: u32,
+    best_score: f32,
+}
+
+impl PlayGame {
+    fn new(config: &Config, nnet: Network) -> Result<Self, Error> {
+        let environment = Environment::new(config, rand::random())?;
+        let floor_bot_y = environment.floor.bounding_box.min.y;
+
+        Ok(Self {
+            player: Player::new(floor_bot_y, nnet),
+            environment,
+            attempts: 0,
+            best_score: 0.0,
+        })
+    }
+}
+
+impl Scene for PlayGame {
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        ctx.game_window.clear(Color::RGB(240, 240, 240));
+
+        self.player.draw(ctx, interpolation)?;
+        self.environment.draw(ctx, interpolation)?;
+
+        let canvas = ctx.game_window.canvas_mut();
+        let score = format!("Score: {:.2}", self.player.score);
+        ctx.text_renderer.draw_text(&score, 10, 10, 0.2, canvas)?;
+
+        let best = format!("Best: {:.2}", self.best_score);
+        ctx.text_renderer.draw_text(&best, 10, 35, 0.2, canvas)?;
+
+        let attempts = format!("Attempt: {}", self.attempts + 1);
+        ctx.text_renderer
+            .draw_text(&attempts, 10, 60, 0.2, canvas)?;
+
+        draw_status(ctx)
+    }
+
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        handle_common_input(ctx)
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        self.player.update(ctx.step_s, &self.environment);
+
+        if self.player.alive {
+            self.environment.update(ctx.step_s);
+        } else {
+            self.best_score = self.best_score.max(self.player.score);
+            self.attempts += 1;
+
+            let floor_bot_y = self.environment.floor.bounding_box.min.y;
+            self.player.reset(floor_bot_y);
+            self.environment.restart();
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn on_resize(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        let width = ctx.game_window.logical_size().0 as f32;
+        self.environment.resize(width)
+    }
+
+    fn progress(&self) -> (u32, f32) {
+        (self.attempts, self.best_score)
+    }
+}
+
+/// Plays back the best player of a recorded generation, starting over
+/// whenever it dies.
+struct ReplayGame {
+    replay: Replay<ObstacleKind, Controls>,
+    player: Player,
+    environment: Environment,
+    step: usize,
+}
+
+impl ReplayGame {
+    fn new(replay: Replay<ObstacleKind, Controls>) -> Result<Self, Error> {
+        let mut environment = Environment::new(&replay.config, replay.seed)?;
+        let floor_bot_y = environment.floor.bounding_box.min.y;
+        environment.script = Some(
+            replay
+                .spawns
+                .iter()
+                .map(|spawn| spawn.kind)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        );
+        environment.restart();
+
+        Ok(Self {
+            replay,
+            player: Player::new(floor_bot_y, Network::default()),
+            environment,
+            step: 0,
+        })
+    }
+
+    /// Advances the replay by one fixed step. Returns false once the player
+    /// died or ran out of recorded actions.
+    fn step(&mut self, step_s: f32) -> bool {
+        let controls = match self.replay.actions.get(self.step) {
+            Some(&controls) => controls,
+            None => return false,
+        };
+        if self.player.collides(step_s, &self.environment) {
+            return false;
+        }
+
+        self.player.act(controls, step_s, &self.environment);
+        self.environment.update(step_s);
+        self.step += 1;
+
+        true
+    }
+}
+
+impl Scene for ReplayGame {
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        ctx.game_window.clear(Color::RGB(240, 240, 240));
+
+        self.player.draw(ctx, interpolation)?;
+        self.environment.draw(ctx, interpolation)?;
+
+        let canvas = ctx.game_window.canvas_mut();
+        let score = format!("Score: {:.2}", self.player.score);
+        ctx.text_renderer.draw_text(&score, 10, 10, 0.2, canvas)?;
+
+        let gen = format!(
+            "Replay of generation {} ({:.2})",
+            self.replay.generation, self.replay.score
+        );
+        ctx.text_renderer.draw_text(&gen, 10, 35, 0.2, canvas)?;
+
+        draw_status(ctx)
+    }
+
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        handle_common_input(ctx)
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        if !self.step(ctx.step_s) {
+            *self = Self::new(self.replay.clone())?;
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn progress(&self) -> (u32, f32) {
+        (self.replay.generation, self.replay.score)
+    }
+}
+
+/// Lists the key bindings over the mode it was opened from, which does not
+/// update meanwhile.
+struct HelpScene;
+
+impl Scene for HelpScene {
+    fn draw(&mut self, ctx: &mut Context, _interpolation: f32) -> Result<(), Error> {
+        const LINE_HEIGHT: i32 = 22;
+
+        let (width, height) = ctx.game_window.logical_size();
+        let center = width as i32 / 2;
+        let top = (height as i32 - Action::COUNT as i32 * LINE_HEIGHT) / 2;
+        let canvas = ctx.game_window.canvas_mut();
+
+        // The mode below shows through the panel.
+        let blend_mode = canvas.blend_mode();
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(255, 255, 255, 220));
+        canvas.fill_rect(None)?;
+        canvas.set_blend_mode(blend_mode);
+
+        let key_style = TextStyle {
+            scale: 0.15,
+            align: TextAlign::Right,
+            ..Default::default()
+        };
+        let description_style = TextStyle {
+            align: TextAlign::Left,
+            ..key_style
+        };
+        for (i, action) in Action::ALL.iter().enumerate() {
+            let y = top + i as i32 * LINE_HEIGHT;
+            let key = ctx.input.key(*action).name();
+            ctx.text_renderer
+                .draw_text_styled(&key, center - 10, y, &key_style, canvas)?;
+            ctx.text_renderer.draw_text_styled(
+                action.description(),
+                center + 10,
+                y,
+                &description_style,
+                canvas,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error> {
+        // Nothing is drawn while fast-forwarding, which would hide the help.
+        if let SimulationState::FastForward(_) = ctx.state {
+            ctx.state = SimulationState::Running;
+        }
+
+        if ctx.input.is_action_pressed(Action::Quit) {
+            ctx.game_window.close();
+        }
+
+        if ctx.input.was_action_just_pressed(Action::ToggleHelp)
+            || ctx.input.was_action_just_pressed(Action::Back)
+        {
+            return Ok(Transition::Pop);
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn update(&mut self, _ctx: &mut Context) -> Result<Transition, Error> {
+        Ok(Transition::Stay)
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+/// Handles the keys shared by all game modes. Escape leaves the mode and H
+/// opens the help.
+fn handle_common_input(ctx: &mut Context) -> Result<Transition, Error> {
+    if ctx.input.is_action_pressed(Action::Quit) {
+        ctx.game_window.close();
+    }
+
+    if ctx.input.was_action_just_pressed(Action::Back) {
+        return Ok(Transition::Pop);
+    }
+    if ctx.input.was_action_just_pressed(Action::ToggleHelp) {
+        return Ok(Transition::Push(Box::new(HelpScene)));
+    }
+
+    if ctx.input.is_action_pressed(Action::SpeedUp) {
+        ctx.speed += 0.3 * ctx.step_s;
+    }
+    if ctx.input.is_action_pressed(Action::SlowDown) {
+        ctx.speed -= 0.3 * ctx.step_s;
+        ctx.speed = ctx.speed.max(0.1);
+    }
+
+    // Pause toggles, Step advances a paused simulation by one update.
+    if ctx.input.was_action_just_pressed(Action::Pause) {
+        ctx.state = match ctx.state {
+            SimulationState::Running => SimulationState::Paused,
+            _ => SimulationState::Running,
+        };
+    }
+    if ctx.input.was_action_just_pressed(Action::Step) && ctx.state == SimulationState::Paused {
+        ctx.state = SimulationState::Stepping;
+    }
+
+    if ctx.input.was_action_just_pressed(Action::ToggleDebug) {
+        ctx.show_debug = !ctx.show_debug;
+    }
+
+    if ctx.input.was_action_just_pressed(Action::ToggleVsync) {
+        let vsync = !ctx.game_window.vsync();
+        ctx.game_window.set_vsync(vsync)?;
+    }
+
+    // Zoom the view around the center of the window.
+    let (width, height) = ctx.game_window.logical_size();
+    let center = Vector2f::from_coords(width as f32, height as f32) / 2.0;
+    if ctx.input.was_action_just_pressed(Action::ZoomIn) {
+        ctx.game_window.camera_mut().zoom_at(center, 1.25);
+    }
+    if ctx.input.was_action_just_pressed(Action::ZoomOut) {
+        ctx.game_window.camera_mut().zoom_at(center, 0.8);
+    }
+    if ctx.input.was_action_just_pressed(Action::ResetZoom) {
+        *ctx.game_window.camera_mut() = Camera::default();
+    }
+
+    Ok(Transition::Stay)
+}
+
+/// Draws the simulation speed and, if enabled, the debug line and the time
+/// spent in every phase of a frame.
+fn draw_status(ctx: &mut Context) -> Result<(), Error> {
+    let speed = match ctx.state {
+        SimulationState::Running => format!("Speed: {:.1}", ctx.speed),
+        _ => format!("Speed: {:.1} (paused)", ctx.speed),
+    };
+    let canvas = ctx.game_window.canvas_mut();
+    ctx.text_renderer.draw_text(&speed, 10, 110, 0.2, canvas)?;
+
+    if ctx.show_debug {
+        let debug = format!(
+            "FPS: {:.0} UPS: {:.0} VSync: {}",
+            ctx.game_window.fps(),
+            ctx.ups.rate(),
+            if ctx.game_window.vsync() { "on" } else { "off" }
+        );
+        let canvas = ctx.game_window.canvas_mut();
+        ctx.text_renderer.draw_text(&debug, 10, 135, 0.2, canvas)?;
+
+        // The profile sits in the lower left corner, out of the way of the
+        // leaderboard.
+        let height = ctx.game_window.logical_size().1 as i32;
+        let canvas = ctx.game_window.canvas_mut();
+        for (i, phase) in Phase::ALL.iter().enumerate() {
+            let line = format!(
+                "{}: {:.2} ms",
+                phase.name(),
+                ctx.profiler.average_ms(*phase)
+            );
+            let y = height - (Phase::COUNT - i) as i32 * 20 - 10;
+            ctx.text_renderer.draw_text(&line, 10, y, 0.15, canvas)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the simulation and genetic algorithm as fast as possible without
+/// creating any window. Every step counts as one frame of the profile.
+fn run_headless(
+    args: &Args,
+    config: &Config,
+    seed: u64,
+    mut the_game: DinaiGame,
+) -> Result<(), Error> {
+    info!("Seed: {}", seed);
+
+    #[cfg(feature = "metrics")]
+    serve_metrics(args, the_game.metrics.clone())?;
+
+    let mut profiler = Profiler::new();
+    let mut profile = args
+        .profile
+        .as_ref()
+        .map(ProfileRecorder::create)
+        .transpose()?;
+
+    let last_generation = args
+        .generations
+        .map(|n| the_game.population.generation().saturating_add(n));
+
+    while last_generation.is_none_or(|last| the_game.population.generation() < last) {
+        let start = Instant::now();
+        the_game.step(config.simulation.step_s, &mut profiler)?;
+        profiler.add(Phase::Update, start.elapsed());
+
+        let frame = profiler.end_frame();
+        if let Some(recorder) = &mut profile {
+            recorder.record(&frame)?;
+        }
+    }
+
+    if let Some(recorder) = &mut profile {
+        recorder.flush()?;
+    }
+
+    Ok(())
+}
+
+const USAGE: &str = "\
+Usage: dinai [OPTIONS]
+       dinai play --network <path> [OPTIONS]
+
+Unless play, --headless, --load, --resume or --replay chooses the mode, a menu is shown.
+
+Commands:
+  play                    Run a single player with a saved network, without training
+
+Options:
+  --config <path>         Load simulation parameters from a TOML file, reloaded while training
+  --population <n>        Number of players in every generation
+  --seed <n>              Seed of the random number generator
+  --headless              Train without opening a window
+  --generations <n>       Stop headless training after n generations
+  --load <path>           Start from a network saved as JSON
+  --network <path>        Network saved as JSON played by the play command
+  --resume <path>         Continue training from a checkpoint
+  --stats <path>          Write statistics of every generation as CSV
+  --speed <x>             Initial simulation speed multiplier
+  --window-size <WxH>     Size of the window, e.g. 1280x720
+  --record <path>         Record the window with ffmpeg, e.g. into run.mp4
+  --record-interval <n>   Record every n-th frame
+  --save-champion <path>  Save the best network of all generations as JSON
+  --save-replays <dir>    Save the best player of every generation as a replay
+  --replay <path>         Watch a saved replay instead of training
+  --fast-forward <n>      Skip drawing of the next n generations
+  --target-score <x>      Skip drawing until a player reaches a score
+  --profile <path>        Write the time spent in every phase of each frame as CSV
+  --metrics-addr <addr>   Serve training metrics on this address (default 127.0.0.1:9898)
+  --log-level <level>     Log only up to off, error, warn, info (default), debug or trace
+  --help                  Print this message";
+
+/// Options given on the command line.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Args {
+    config: Option<String>,
+    population: Option<usize>,
+    seed: Option<u64>,
+    headless: bool,
+    generations: Option<u32>,
+    load: Option<String>,
+    resume: Option<String>,
+    stats: Option<String>,
+    speed: Option<f32>,
+    window_size: Option<(u32, u32)>,
+    record: Option<String>,
+    record_interval: Option<u32>,
+    fast_forward: Option<u32>,
+    target_score: Option<f32>,
+    play: bool,
+    network: Option<String>,
+    save_replays: Option<String>,
+    save_champion: Option<String>,
+    replay: Option<String>,
+    profile: Option<String>,
+    metrics_addr: Option<String>,
+    log_level: Option<LevelFilter>,
+    help: bool,
+}
+
+impl Args {
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut res = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--config" => res.config = Some(value()?),
+                "--population" => res.population = Some(parse_value(&value()?)?),
+                "--seed" => res.seed = Some(parse_value(&value()?)?),
+                "--headless" => res.headless = true,
+                "--generations" => res.generations = Some(parse_value(&value()?)?),
+                "--load" => res.load = Some(value()?),
+                "--resume" => res.resume = Some(value()?),
+                "--stats" => res.stats = Some(value()?),
+                "--speed" => res.speed = Some(parse_value(&value()?)?),
+                "--window-size" => res.window_size = Some(parse_size(&value()?)?),
+                "--record" => res.record = Some(value()?),
+                "--record-interval" => res.record_interval = Some(parse_value(&value()?)?),
+                "--fast-forward" => res.fast_forward = Some(parse_value(&value()?)?),
+                "--target-score" => res.target_score = Some(parse_value(&value()?)?),
+                "play" => res.play = true,
+                "--network" => res.network = Some(value()?),
+                "--save-replays" => res.save_replays = Some(value()?),
+                "--save-champion" => res.save_champion = Some(value()?),
+                "--replay" => res.replay = Some(value()?),
+                "--profile" => res.profile = Some(value()?),
+                "--metrics-addr" => res.metrics_addr = Some(value()?),
+                "--log-level" => res.log_level = Some(parse_value(&value()?)?),
+                "--help" | "-h" => res.help = true,
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+
+        if res.play && res.network.is_none() {
+            return Err("play requires --network".to_string());
+        }
+
+        if res.headless {
+            let window_only = [
+                ("play", res.play),
+                ("--replay", res.replay.is_some()),
+                ("--speed", res.speed.is_some()),
+                ("--record", res.record.is_some()),
+                ("--record-interval", res.record_interval.is_some()),
+                ("--fast-forward", res.fast_forward.is_some()),
+                ("--target-score", res.target_score.is_some()),
+            ];
+            if let Some((name, _)) = window_only.iter().find(|(_, given)| *given) {
+                return Err(format!("{} cannot be used with --headless", name));
+            }
+        } else if res.generations.is_some() {
+            return Err("--generations requires --headless".to_string());
+        }
+
+        Ok(res)
+    }
+
+    /// Overrides the values of `config` given on the command line.
+    fn apply(&self, config: &mut Config) -> Result<(), Error> {
+        if let Some(population) = self.population {
+            config.simulation.population_size = population;
+        }
+
+        if let Some((width, height)) = self.window_size {
+            config.window.width = width;
+            config.window.height = height;
+        }
+
+        config.validate()
+    }
+
+    /// Returns true unless a mode is chosen on the command line, in which
+    /// case the menu is shown to choose one.
+    fn shows_menu(&self) -> bool {
+        !(self.headless
+            || self.play
+            || self.load.is_some()
+            || self.resume.is_some()
+            || self.replay.is_some())
+    }
+
+    /// Returns where fast-forwarding requested on the command line stops
+    /// for a game starting at `generation`.
+    fn fast_forward_target(&self, generation: u32) -> Option<FastForward> {
+        if self.fast_forward.is_none() && self.target_score.is_none() {
+            return None;
+        }
+
+        Some(FastForward {
+            until_generation: self
+                .fast_forward
+                .map_or(u32::MAX, |n| generation.saturating_add(n)),
+            target_score: self.target_score,
+        })
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {}", value))
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let mut parts = value.splitn(2, 'x');
+    match (parts.next(), parts.next()) {
+        (Some(width), Some(height)) => Ok((parse_value(width)?, parse_value(height)?)),
+        _ => Err(format!("invalid size {}, expected WxH", value)),
+    }
+}
+
+/// Writes log records to stderr, prefixed by their level and module.
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if args.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(args.log_level.unwrap_or(LevelFilter::Info));
+    }
+
+    if let Err(err) = run(args) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    args.apply(&mut config)?;
+
+    #[cfg(not(feature = "metrics"))]
+    if args.metrics_addr.is_some() {
+        return Err(Error::Config(
+            "serving metrics requires the metrics feature".to_string(),
+        ));
+    }
+
+    if args.shows_menu() {
+        let menu = MenuScene::new(&args, &config);
+        #[cfg(feature = "metrics")]
+        serve_metrics(&args, menu.metrics.clone())?;
+
+        return run_window(&args, &config, Box::new(menu));
+    }
+
+    if let Some(path) = &args.replay {
+        let replay: Replay<ObstacleKind, Controls> = Replay::load(path)?;
+        replay.config.validate()?;
+        let config = replay.config.clone();
+        return run_window(&args, &config, Box::new(ReplayGame::new(replay)?));
+    }
+
+    if args.play {
+        let path = args.network.as_deref().unwrap_or_default();
+        let the_game = PlayGame::new(&config, load_network(path)?)?;
+        return run_window(&args, &config, Box::new(the_game));
+    }
+
+    let mut the_game = new_training(&args, &config, seed)?;
+
+    if args.headless {
+        return run_headless(&args, &config, seed, the_game);
+    }
+
+    #[cfg(feature = "metrics")]
+    serve_metrics(&args, the_game.metrics.clone())?;
+
+    the_game.breed_in_background = true;
+    run_window(&args, &config, Box::new(the_game))
+}
+
+/// Serves `metrics` on the address given on the command line. Without one the default address is
+/// used, and if it is taken, e.g. by another run, training continues without metrics.
+#[cfg(feature = "metrics")]
+fn serve_metrics(args: &Args, metrics: Arc<Metrics>) -> Result<(), Error> {
+    match &args.metrics_addr {
+        Some(addr) => metrics::serve(addr.as_str(), metrics)?,
+        None => {
+            if let Err(err) = metrics::serve(METRICS_ADDR, metrics) {
+                warn!("Not serving metrics on {}: {}", METRICS_ADDR, err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a training with the options given on the command line.
+fn new_training(args: &Args, config: &Config, seed: u64) -> Result<DinaiGame, Error> {
+    let mut the_game = DinaiGame::new(config, seed)?;
+
+    if let Some(path) = &args.load {
+        the_game.seed_population(&[load_network(path)?], 0);
+    }
+
+    if let Some(path) = &args.resume {
+        the_game.resume(Checkpoint::load(path)?)?;
+    }
+
+    if let Some(path) = &args.save_champion {
+        the_game.champion_path = Some(PathBuf::from(path));
+    }
+
+    if let Some(dir) = &args.save_replays {
+        the_game.save_replays(PathBuf::from(dir));
+    }
+
+    if let Some(path) = &args.stats {
+        the_game.stats_recorder = Some(StatsRecorder::create(path)?);
+    }
+
+    if let Some(path) = &args.config {
+        the_game.config_watcher = Some(ConfigWatcher::new(path));
+    }
+
+    Ok(the_game)
+}
+
+/// Saves `nnet` as JSON into `path`. The network is written next to it
+/// first, so that a failed write never leaves a truncated file at `path`.
+fn save_network(path: &Path, nnet: &Network) -> Result<(), Error> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    serde_json::to_writer(&mut writer, nnet).map_err(|e| Error::Io(e.into()))?;
+    writer.flush()?;
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+fn load_network(path: &str) -> Result<Network, Error> {
+    let file = File::open(path)?;
+
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| Error::Config(format!("invalid network {}: {}", path, e)))
+}
+
+/// Opens a window and runs `scene` in it until the window is closed.
+fn run_window(args: &Args, config: &Config, scene: Box<dyn Scene>) -> Result<(), Error> {
+    let mut game_loop = GameLoop::new("dinai", config);
+    game_loop.set_speed(args.speed.unwrap_or(config.simulation.speed));
+    // The menu starts fast-forwarding once a training is chosen.
+    if let Some(target) = args
+        .fast_forward_target(scene.progress().0)
+        .filter(|_| !args.shows_menu())
+    {
+        game_loop.set_state(SimulationState::FastForward(target));
+    }
+    if let Some(path) = &args.profile {
+        game_loop.set_profile(ProfileRecorder::create(path)?);
+    }
+
+    #[cfg(feature = "recorder")]
+    if let Some(path) = &args.record {
+        game_loop.set_record(path, args.record_interval.unwrap_or(RECORD_INTERVAL));
+    }
+    #[cfg(not(feature = "recorder"))]
+    if args.record.is_some() {
+        return Err(Error::Config(
+            "recording requires the recorder feature".to_string(),
+        ));
+    }
+
+    game_loop.run(&mut SceneStack::new(scene))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&["--seed", "42", "--headless", "--window-size", "800x600"]).unwrap();
+
+        assert_eq!(
+            args,
+            Args {
+                seed: Some(42),
+                headless: true,
+                window_size: Some((800, 600)),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_args() {
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--log-level", "loud"]).is_err());
+        assert!(parse(&["--population", "many"]).is_err());
+        assert!(parse(&["--window-size", "800"]).is_err());
+        assert!(parse(&["--fast"]).is_err());
+    }
+
+    #[test]
+    fn test_shows_menu() {
+        assert!(parse(&[]).unwrap().shows_menu());
+        assert!(parse(&["--config", "dinai.toml", "--speed", "2"])
+            .unwrap()
+            .shows_menu());
+        assert!(!parse(&["--resume", "checkpoint.json"])
+            .unwrap()
+            .shows_menu());
+        assert!(!parse(&["play", "--network", "best.json"])
+            .unwrap()
+            .shows_menu());
+    }
+
+    #[test]
+    fn test_parse_headless() {
+        let args = parse(&["--headless", "--generations", "3"]).unwrap();
+        assert_eq!(args.generations, Some(3));
+
+        assert!(parse(&["--generations", "3"]).is_err());
+        assert!(parse(&["--headless", "--speed", "2"]).is_err());
+        assert!(parse(&["--headless", "--fast-forward", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_play() {
+        let args = parse(&["play", "--network", "best.json"]).unwrap();
+
+        assert!(args.play);
+        assert_eq!(args.network.as_deref(), Some("best.json"));
+        assert!(parse(&["play"]).is_err());
+    }
+
+    #[test]
+    fn test_replay_repeats_best_player() {
+        let dir = std::env::temp_dir().join(format!("dinai-replays-{}", process::id()));
+        let mut config = Config::default();
+        config.simulation.population_size = 20;
+
+        let mut game = DinaiGame::new(&config, 7).unwrap();
+        game.save_replays(dir.clone());
+        while game.population.generation() < 3 {
+            game.step(config.simulation.step_s, &mut Profiler::new())
+                .unwrap();
+        }
+
+        let replay: Replay<ObstacleKind, Controls> =
+            Replay::load(dir.join("generation-00002.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut replay_game = ReplayGame::new(replay).unwrap();
+        while replay_game.step(config.simulation.step_s) {}
+
+        assert_eq!(replay_game.player.score, replay_game.replay.score);
+    }
+
+    #[test]
+    fn test_tuning_adjust() {
+        let mut tuning = Tuning {
+            mutation_rate: 0.0,
+            mutation_sigma: 0.2,
+            elite_count: 2,
+            population_size: 60,
+            obstacle_speed: 400.0,
+            obstacle_max_speed: 2000.0,
+        };
+
+        tuning.adjust(0, -1);
+        assert_eq!(tuning.mutation_rate, 0.0);
+
+        tuning.adjust(3, -2);
+        assert_eq!(tuning.population_size, 2);
+        assert_eq!(tuning.elite_count, 1);
+
+        tuning.adjust(2, 5);
+        assert_eq!(tuning.elite_count, 1);
+
+        tuning.adjust(5, -30);
+        assert_eq!(tuning.obstacle_max_speed, 100.0);
+
+        tuning.set(0, 0.1);
+        assert_eq!(tuning.mutation_rate, 0.1);
+
+        tuning.set(5, 0.0);
+        assert_eq!(tuning.obstacle_max_speed, 100.0);
+    }
+
+    #[test]
+    fn test_controls_bits() {
+        for bits in 0..4u8 {
+            assert_eq!(u8::from(Controls::from(bits)), bits);
+        }
+
+        let json = serde_json::to_string(&[Controls {
+            jump: true,
+            duck: false,
+        }])
+        .unwrap();
+        assert_eq!(json, "[1]");
+    }
+
+    #[test]
+    fn test_fast_forward_target() {
+        assert_eq!(parse(&[]).unwrap().fast_forward_target(0), None);
+
+        let target = parse(&["--target-score", "30"])
+            .unwrap()
+            .fast_forward_target(0)
+            .unwrap();
+        assert!(!target.is_reached(1000, 29.0));
+        assert!(target.is_reached(0, 30.0));
+
+        let target = parse(&["--fast-forward", "5"])
+            .unwrap()
+            .fast_forward_target(0)
+            .unwrap();
+        assert!(!target.is_reached(4, 100.0));
+        assert!(target.is_reached(5, 0.0));
+
+        // A resumed run skips the next generations, not those up to an
+        // absolute one.
+        let target = parse(&["--fast-forward", "5"])
+            .unwrap()
+            .fast_forward_target(41)
+            .unwrap();
+        assert!(!target.is_reached(45, 0.0));
+        assert!(target.is_reached(46, 0.0));
+    }
 }
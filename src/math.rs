@@ -1,6 +1,10 @@
 //! A module for operations related to math.
 
+use crate::genetic::MutationConfig;
 use rand::distributions::uniform::SampleUniform;
+use rand::Rng;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
 use std::ops;
 
 /// Performs the sigmoid function.
@@ -8,23 +12,70 @@ pub fn sigmoid(x: f32) -> f32 {
     1.0 / (1.0 + std::f32::consts::E.powf(-x))
 }
 
-/// A 2D `f32` vector.
-#[derive(Debug, Copy, Clone, Default)]
-pub struct Vector2f {
+/// Performs the rectified linear unit function.
+pub fn relu(x: f32) -> f32 {
+    x.max(0.0)
+}
+
+/// Performs the leaky rectified linear unit function with the given slope for negative values.
+pub fn leaky_relu(x: f32, slope: f32) -> f32 {
+    if x > 0.0 {
+        x
+    } else {
+        x * slope
+    }
+}
+
+/// Performs the softmax function on every row of the given matrix, so that each row sums up to
+/// one.
+pub fn softmax<const ROWS: usize, const COLS: usize>(matrix: &mut Matrix<f32, ROWS, COLS>) {
+    for row in matrix.data.iter_mut() {
+        // Subtracting the maximum keeps the exponentials from overflowing.
+        let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        let mut sum = 0.0;
+        for cell in row.iter_mut() {
+            *cell = (*cell - max).exp();
+            sum += *cell;
+        }
+
+        for cell in row.iter_mut() {
+            *cell /= sum;
+        }
+    }
+}
+
+/// A generic 2D vector.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Vector2<T> {
     /// x coordinate of the vector.
-    pub x: f32,
+    pub x: T,
 
     /// y coordinate of the vector.
-    pub y: f32,
+    pub y: T,
 }
 
-impl Vector2f {
-    /// Creates a new `Vector2f` with default values.
+/// A 2D `f32` vector.
+pub type Vector2f = Vector2<f32>;
+
+/// A 2D `f64` vector.
+pub type Vector2d = Vector2<f64>;
+
+/// A 2D `i32` vector, e.g. a position on the screen.
+pub type Vector2i = Vector2<i32>;
+
+/// A 2D `u32` vector, e.g. a size on the screen.
+pub type Vector2u = Vector2<u32>;
+
+impl<T: Default> Vector2<T> {
+    /// Creates a new vector with default values.
     pub fn new() -> Self {
         Default::default()
     }
+}
 
-    /// Creates new `Vector2f` with given `x` and `y` coordinates.
+impl<T> Vector2<T> {
+    /// Creates new vector with given `x` and `y` coordinates.
     ///
     /// # Examples
     ///
@@ -34,13 +85,153 @@ impl Vector2f {
     ///
     /// assert!(((vector.x - vector.y) - (1.23 - 3.21)).abs() < 0.00001);
     /// ```
-    pub fn from_coords(x: f32, y: f32) -> Self {
+    pub fn from_coords(x: T, y: T) -> Self {
         Self { x, y }
     }
+
+    /// Applies `func` to both coordinates producing a new vector.
+    pub fn map<U, F: Fn(T) -> U>(self, func: F) -> Vector2<U> {
+        Vector2 {
+            x: func(self.x),
+            y: func(self.y),
+        }
+    }
+
+    /// Losslessly converts the coordinates to another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{Vector2d, Vector2i};
+    ///
+    /// let vector: Vector2d = Vector2i::from_coords(3, -4).cast();
+    ///
+    /// assert_eq!(vector, Vector2d::from_coords(3.0, -4.0));
+    /// ```
+    pub fn cast<U: From<T>>(self) -> Vector2<U> {
+        self.map(U::from)
+    }
+}
+
+/// Implements numeric conversions with the semantics of `as` between vectors of primitive types.
+macro_rules! impl_vector2_as {
+    ($($t:ty),*) => {
+        $(
+            impl Vector2<$t> {
+                /// Converts the coordinates to `f32` as the `as` operator does.
+                pub fn as_vector2f(self) -> Vector2f {
+                    self.map(|v| v as f32)
+                }
+
+                /// Converts the coordinates to `f64` as the `as` operator does.
+                pub fn as_vector2d(self) -> Vector2d {
+                    self.map(|v| v as f64)
+                }
+
+                /// Converts the coordinates to `i32` as the `as` operator does, i.e. floats are
+                /// truncated and saturated.
+                pub fn as_vector2i(self) -> Vector2i {
+                    self.map(|v| v as i32)
+                }
+
+                /// Converts the coordinates to `u32` as the `as` operator does, i.e. floats are
+                /// truncated and negative values saturate to zero.
+                pub fn as_vector2u(self) -> Vector2u {
+                    self.map(|v| v as u32)
+                }
+            }
+
+            impl ops::Mul<Vector2<$t>> for $t {
+                type Output = Vector2<$t>;
+
+                fn mul(self, rhs: Vector2<$t>) -> Vector2<$t> {
+                    rhs * self
+                }
+            }
+        )*
+    };
+}
+
+impl_vector2_as!(f32, f64, i32, u32);
+
+impl Vector2f {
+    /// Returns the dot product of this vector and `other`.
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the squared length of this vector, which is cheaper to compute than
+    /// [`length`](#method.length).
+    pub fn length_squared(&self) -> f32 {
+        self.dot(self)
+    }
+
+    /// Returns the length of this vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dinai::math::Vector2f;
+    /// let vector = Vector2f::from_coords(3.0, 4.0);
+    ///
+    /// assert!((vector.length() - 5.0).abs() < 0.00001);
+    /// ```
+    pub fn length(&self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    /// Returns a vector of length one pointing in the same direction. A zero vector stays zero.
+    pub fn normalize(&self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+
+        Self::from_coords(self.x / length, self.y / length)
+    }
+
+    /// Returns the distance between the points described by this vector and `other`.
+    pub fn distance_to(&self, other: &Self) -> f32 {
+        (*other - *self).length()
+    }
+
+    /// Linearly interpolates between this vector (`t == 0.0`) and `other` (`t == 1.0`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dinai::math::Vector2f;
+    /// let a = Vector2f::from_coords(0.0, 10.0);
+    /// let b = Vector2f::from_coords(10.0, 20.0);
+    ///
+    /// let mid = a.lerp(&b, 0.5);
+    ///
+    /// assert!((mid.x - 5.0).abs() < 0.00001 && (mid.y - 15.0).abs() < 0.00001);
+    /// ```
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+
+    /// Returns this vector rotated by `angle` radians. With the y-axis pointing down, positive
+    /// angles rotate clockwise on the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dinai::math::Vector2f;
+    /// let rotated = Vector2f::from_coords(1.0, 0.0).rotate(std::f32::consts::FRAC_PI_2);
+    ///
+    /// assert!(rotated.x.abs() < 0.00001 && (rotated.y - 1.0).abs() < 0.00001);
+    /// ```
+    pub fn rotate(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::from_coords(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
 }
 
 /// An axis-aligned bounding box.
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct AABBf {
     /// The minimal point of this box (upper left corner).
     pub min: Vector2f,
@@ -49,6 +240,103 @@ pub struct AABBf {
     pub max: Vector2f,
 }
 
+/// A circle.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Circlef {
+    /// Center of the circle.
+    pub center: Vector2f,
+
+    /// Radius of the circle.
+    pub radius: f32,
+}
+
+/// A capsule, i.e. all points within `radius` of the segment from `start` to `end`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Capsulef {
+    /// First end point of the inner segment.
+    pub start: Vector2f,
+
+    /// Second end point of the inner segment.
+    pub end: Vector2f,
+
+    /// Radius around the inner segment.
+    pub radius: f32,
+}
+
+/// An oriented bounding box, i.e. a box rotated around its center.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OBBf {
+    /// Center of the box.
+    pub center: Vector2f,
+
+    /// Half of the width and height of the box before rotation.
+    pub half_size: Vector2f,
+
+    /// Rotation of the box around its center in radians, see [`Vector2f::rotate`].
+    ///
+    /// [`Vector2f::rotate`]: struct.Vector2.html#method.rotate
+    pub rotation: f32,
+}
+
+/// A collider of any supported shape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Shape {
+    /// An axis-aligned box.
+    Aabb(AABBf),
+
+    /// A circle.
+    Circle(Circlef),
+
+    /// A capsule.
+    Capsule(Capsulef),
+
+    /// A rotated box.
+    Obb(OBBf),
+}
+
+/// Describes how two intersecting boxes touch, see [`AABBf::contact`].
+///
+/// [`AABBf::contact`]: struct.AABBf.html#method.contact
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    /// Direction in which the first box has to move to separate from the second one.
+    pub normal: Vector2f,
+
+    /// Distance the first box has to move along `normal` to separate.
+    pub depth: f32,
+
+    /// Center of the overlapping area.
+    pub point: Vector2f,
+}
+
+/// The point where a ray hits a box, see [`AABBf::raycast`].
+///
+/// [`AABBf::raycast`]: struct.AABBf.html#method.raycast
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit {
+    /// Distance from the origin of the ray to the hit point.
+    pub distance: f32,
+
+    /// The hit point on the border of the box.
+    pub point: Vector2f,
+
+    /// Normal of the hit surface. Zero if the ray starts inside the box.
+    pub normal: Vector2f,
+}
+
+/// The first contact of a box moving towards another box, see [`AABBf::sweep`].
+///
+/// [`AABBf::sweep`]: struct.AABBf.html#method.sweep
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the movement in `[0, 1]` after which the boxes touch.
+    pub time: f32,
+
+    /// Normal of the hit surface of the other box. Zero if the boxes already intersect at the
+    /// start of the movement.
+    pub normal: Vector2f,
+}
+
 /// A generic 2D matrix.
 #[derive(Debug, Clone)]
 pub struct Matrix<T, const ROWS: usize, const COLS: usize> {
@@ -61,11 +349,103 @@ impl<T: Copy + Default, const ROWS: usize, const COLS: usize> Default for Matrix
     }
 }
 
+impl<T, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS> {
+    /// Returns a reference to the value at the given row and column, or `None` if the position is
+    /// out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    ///
+    /// let matrix = Matrix::from([[1, 2], [3, 4]]);
+    ///
+    /// assert_eq!(matrix.get(1, 0), Some(&3));
+    /// assert_eq!(matrix.get(2, 0), None);
+    /// ```
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.data.get(row).and_then(|r| r.get(col))
+    }
+
+    /// Returns a mutable reference to the value at the given row and column, or `None` if the
+    /// position is out of bounds.
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.data.get_mut(row).and_then(|r| r.get_mut(col))
+    }
+
+    /// Returns an iterator over all cells in row-major order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    ///
+    /// let matrix = Matrix::from([[1.0, -4.0], [3.0, 2.0]]);
+    ///
+    /// let max = matrix.iter().map(|x: &f32| x.abs()).fold(0.0, f32::max);
+    ///
+    /// assert_eq!(max, 4.0);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter().flatten()
+    }
+
+    /// Returns an iterator over mutable references to all cells in row-major order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut().flatten()
+    }
+
+    /// Returns an iterator over the rows of this matrix.
+    pub fn rows(&self) -> impl Iterator<Item = &[T; COLS]> {
+        self.data.iter()
+    }
+
+    /// Returns an iterator over the columns of this matrix, each being an iterator over its cells
+    /// from the top.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    ///
+    /// let matrix = Matrix::from([[1, 2], [3, 4]]);
+    ///
+    /// let sums: Vec<i32> = matrix.cols().map(|col| col.sum()).collect();
+    ///
+    /// assert_eq!(sums, [4, 6]);
+    /// ```
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..COLS).map(move |x| self.data.iter().map(move |row| &row[x]))
+    }
+}
+
 impl<T: Default + Copy, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS> {
     /// Creates new `Matrix` with default values.
     pub fn new() -> Self {
         Self::with_val(Default::default())
     }
+
+    /// Returns the transpose of this matrix, i.e. a matrix with rows and columns swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    ///
+    /// let matrix = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+    ///
+    /// assert_eq!(matrix.transpose().as_ref(), &[[1, 4], [2, 5], [3, 6]]);
+    /// ```
+    pub fn transpose(&self) -> Matrix<T, COLS, ROWS> {
+        let mut res = Matrix::new();
+        for y in 0..ROWS {
+            for x in 0..COLS {
+                res.data[x][y] = self.data[y][x];
+            }
+        }
+
+        res
+    }
 }
 
 impl<T: Copy, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS> {
@@ -110,14 +490,68 @@ where
     ) -> Matrix<T, ROWS, OTH_COLS> {
         let mut res: Matrix<T, ROWS, OTH_COLS> = Matrix::new();
 
-        for ly in 0..ROWS {
-            for rx in 0..OTH_COLS {
-                let mut val = Default::default();
-                for lx in 0..COLS {
-                    val += self.data[ly][lx] * rhs.data[lx][rx];
+        // Walking the rows of `rhs` in the inner loop reads memory sequentially and lets the
+        // compiler vectorize the loop. Every cell still sums its products in the same order.
+        for (res_row, row) in res.data.iter_mut().zip(self.data.iter()) {
+            for (&val, rhs_row) in row.iter().zip(rhs.data.iter()) {
+                for (cell, &rhs_val) in res_row.iter_mut().zip(rhs_row.iter()) {
+                    *cell += val * rhs_val;
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<const ROWS: usize, const COLS: usize> Matrix<f32, ROWS, COLS> {
+    /// Multiplies this matrix with the `rhs` matrix on the right like [`mul_matrix`].
+    ///
+    /// With the `simd` feature sixteen cells of a row of the result are summed at once in an
+    /// array the compiler keeps in vector registers, which is faster once `rhs` has at least
+    /// sixteen columns. Every cell sums its products in the same order, so the result is the same.
+    ///
+    /// [`mul_matrix`]: #method.mul_matrix
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    ///
+    /// let a = Matrix::from([[1.0, 2.0]]);
+    /// let b = Matrix::from([[3.0], [4.0]]);
+    ///
+    /// assert_eq!(a.mul_matrix_f32(&b).as_ref(), a.mul_matrix(&b).as_ref());
+    /// ```
+    pub fn mul_matrix_f32<const OTH_COLS: usize>(
+        &self,
+        rhs: &Matrix<f32, COLS, OTH_COLS>,
+    ) -> Matrix<f32, ROWS, OTH_COLS> {
+        if !cfg!(feature = "simd") {
+            return self.mul_matrix(rhs);
+        }
+
+        const LANES: usize = 16;
+        let mut res: Matrix<f32, ROWS, OTH_COLS> = Matrix::new();
+
+        for (res_row, row) in res.data.iter_mut().zip(self.data.iter()) {
+            let mut cells = res_row.chunks_exact_mut(LANES);
+            for (i, cells) in (&mut cells).enumerate() {
+                let start = i * LANES;
+                let mut sums = [0.0; LANES];
+                for (&val, rhs_row) in row.iter().zip(rhs.data.iter()) {
+                    for (sum, &rhs_val) in sums.iter_mut().zip(&rhs_row[start..start + LANES]) {
+                        *sum += val * rhs_val;
+                    }
                 }
+                cells.copy_from_slice(&sums);
+            }
 
-                res.data[ly][rx] = val;
+            let start = OTH_COLS - cells.into_remainder().len();
+            for (&val, rhs_row) in row.iter().zip(rhs.data.iter()) {
+                for (cell, &rhs_val) in res_row[start..].iter_mut().zip(&rhs_row[start..]) {
+                    *cell += val * rhs_val;
+                }
             }
         }
 
@@ -141,13 +575,39 @@ where
 
 impl<T, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS>
 where
-    T: Default + Copy + SampleUniform,
+    T: Copy + std::ops::SubAssign,
 {
-    /// Creates new `Matrix` with random values.
-    pub fn with_random(low: T, high: T) -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+    /// Performs subtraction of a matrix on the right. These matrices must have same dimensions.
+    pub fn sub_matrix(&mut self, rhs: &Matrix<T, ROWS, COLS>) {
+        for y in 0..ROWS {
+            for x in 0..COLS {
+                self.data[y][x] -= rhs.data[y][x];
+            }
+        }
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS>
+where
+    T: Copy + std::ops::MulAssign,
+{
+    /// Multiplies every cell of this matrix with the cell at the same position of `rhs`
+    /// (Hadamard product). These matrices must have same dimensions.
+    pub fn mul_elementwise(&mut self, rhs: &Matrix<T, ROWS, COLS>) {
+        for y in 0..ROWS {
+            for x in 0..COLS {
+                self.data[y][x] *= rhs.data[y][x];
+            }
+        }
+    }
+}
 
+impl<T, const ROWS: usize, const COLS: usize> Matrix<T, ROWS, COLS>
+where
+    T: Default + Copy + SampleUniform,
+{
+    /// Creates new `Matrix` with random values drawn from `rng`.
+    pub fn with_random<R: Rng + ?Sized>(low: T, high: T, rng: &mut R) -> Self {
         let mut res = Matrix::new();
         for y in 0..ROWS {
             for x in 0..COLS {
@@ -159,10 +619,7 @@ where
     }
 
     /// Crossovers two matrices at one random position producing a new matrix.
-    pub fn crossover(&self, other: &Matrix<T, ROWS, COLS>) -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Matrix<T, ROWS, COLS>, rng: &mut R) -> Self {
         let pr: usize = rng.gen_range(0, ROWS);
         let pc: usize = rng.gen_range(0, COLS);
 
@@ -178,42 +635,346 @@ where
     }
 }
 
-/// Randomly adds Gaussian random value to every cell of the given matrix.
-pub fn mutate_matrixf<const ROWS: usize, const COLS: usize>(
-    matrix: &mut Matrix<f32, ROWS, COLS>,
-    probability: f32,
-) {
-    use rand::Rng;
-    use rand_distr::StandardNormal;
+/// The way the weights of two parents are combined into a child.
+#[derive(Debug, Copy, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossoverStrategy {
+    /// Takes a rectangular block from the second parent, see [`Matrix::crossover`].
+    ///
+    /// [`Matrix::crossover`]: struct.Matrix.html#method.crossover
+    #[default]
+    Block,
 
-    let mut rng = rand::thread_rng();
-    for row in matrix.data.iter_mut() {
-        for cell in row.iter_mut() {
-            if rng.gen::<f32>() < probability {
-                let val: f32 = rng.sample(StandardNormal);
-                *cell += val / 5.0;
-
-                if *cell > 1.0 {
-                    *cell = 1.0;
-                } else if *cell < -1.0 {
-                    *cell = -1.0;
+    /// Takes every weight from a randomly chosen parent.
+    Uniform,
+
+    /// Takes all incoming weights of a neuron, which form a column, from a randomly chosen
+    /// parent.
+    Neuron,
+
+    /// Averages the weights of both parents.
+    Average,
+}
+
+impl<const ROWS: usize, const COLS: usize> Matrix<f32, ROWS, COLS> {
+    /// Crossovers two matrices as described by `strategy` producing a new matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{CrossoverStrategy, Matrix};
+    ///
+    /// let a = Matrix::from([[1.0, 2.0]]);
+    /// let b = Matrix::from([[3.0, 4.0]]);
+    /// let child = a.crossover_with(&b, CrossoverStrategy::Average, &mut rand::thread_rng());
+    ///
+    /// assert_eq!(child.as_ref(), &[[2.0, 3.0]]);
+    /// ```
+    pub fn crossover_with<R: Rng + ?Sized>(
+        &self,
+        other: &Matrix<f32, ROWS, COLS>,
+        strategy: CrossoverStrategy,
+        rng: &mut R,
+    ) -> Self {
+        let mut res = self.clone();
+
+        match strategy {
+            CrossoverStrategy::Block => return self.crossover(other, rng),
+            CrossoverStrategy::Uniform => {
+                for (cell, &theirs) in res.iter_mut().zip(other.iter()) {
+                    if rng.gen::<bool>() {
+                        *cell = theirs;
+                    }
+                }
+            }
+            CrossoverStrategy::Neuron => {
+                for x in 0..COLS {
+                    if rng.gen::<bool>() {
+                        for y in 0..ROWS {
+                            res.data[y][x] = other.data[y][x];
+                        }
+                    }
+                }
+            }
+            CrossoverStrategy::Average => {
+                for (cell, &theirs) in res.iter_mut().zip(other.iter()) {
+                    *cell = (*cell + theirs) / 2.0;
                 }
             }
         }
+
+        res
     }
 }
 
-impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
-    fn from(data: [[T; C]; R]) -> Self {
-        Self { data }
-    }
+/// Randomly adds Gaussian random value to every cell of the given matrix as described by
+/// `config`.
+pub fn mutate_matrixf<R: Rng + ?Sized, const ROWS: usize, const COLS: usize>(
+    matrix: &mut Matrix<f32, ROWS, COLS>,
+    config: &MutationConfig,
+    rng: &mut R,
+) {
+    mutate_cellsf(matrix.iter_mut(), config, rng);
 }
 
-impl<T: Clone, const R: usize, const C: usize> From<&Matrix<T, R, C>> for Matrix<T, R, C> {
-    #[inline]
-    fn from(matrix: &Matrix<T, R, C>) -> Self {
-        matrix.clone()
-    }
+/// Randomly adds Gaussian random value to every cell of the given dynamically sized matrix as
+/// described by `config`.
+pub fn mutate_dyn_matrixf<R: Rng + ?Sized>(
+    matrix: &mut DynMatrix<f32>,
+    config: &MutationConfig,
+    rng: &mut R,
+) {
+    mutate_cellsf(matrix.data.iter_mut(), config, rng);
+}
+
+fn mutate_cellsf<'a, I, R>(cells: I, config: &MutationConfig, rng: &mut R)
+where
+    I: Iterator<Item = &'a mut f32>,
+    R: Rng + ?Sized,
+{
+    use rand_distr::StandardNormal;
+
+    for cell in cells {
+        *cell *= 1.0 - config.weight_decay;
+
+        if rng.gen::<f32>() < config.rate {
+            let val: f32 = rng.sample(StandardNormal);
+            *cell += val * config.sigma;
+            if let Some(clamp) = config.clamp {
+                *cell = cell.clamp(-clamp, clamp);
+            }
+        }
+    }
+}
+
+/// A 2D matrix with dimensions chosen at runtime.
+///
+/// Unlike [`Matrix`], operations with mismatched dimensions cannot be rejected at compile time and
+/// panic instead.
+///
+/// [`Matrix`]: struct.Matrix.html
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DynMatrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Copy> DynMatrix<T> {
+    /// Creates new `DynMatrix` with the given dimensions filled with `val`.
+    pub fn with_val(rows: usize, cols: usize, val: T) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![val; rows * cols],
+        }
+    }
+
+    /// Creates new `DynMatrix` with a single row containing `values`.
+    pub fn from_row(values: &[T]) -> Self {
+        Self {
+            rows: 1,
+            cols: values.len(),
+            data: values.to_vec(),
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the value at the given row and column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> T {
+        assert!(row < self.rows && col < self.cols, "position out of bounds");
+        self.data[row * self.cols + col]
+    }
+
+    /// Returns all cells in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Applies given operation to every cell of this matrix.
+    pub fn apply<U>(&mut self, func: U)
+    where
+        U: Fn(T) -> T,
+    {
+        for cell in self.data.iter_mut() {
+            *cell = func(*cell);
+        }
+    }
+}
+
+impl<T: Copy + Default> DynMatrix<T> {
+    /// Creates new `DynMatrix` with the given dimensions and default values.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self::with_val(rows, cols, Default::default())
+    }
+}
+
+impl<T> DynMatrix<T>
+where
+    T: Copy + Default + ops::Mul<Output = T> + ops::AddAssign,
+{
+    /// Multiplies this matrix with the `rhs` matrix on the right producing a new matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of columns of this matrix differs from the number of rows of `rhs`.
+    pub fn mul_matrix(&self, rhs: &DynMatrix<T>) -> DynMatrix<T> {
+        assert_eq!(self.cols, rhs.rows, "incompatible matrix dimensions");
+
+        let mut res = DynMatrix::new(self.rows, rhs.cols);
+
+        for ly in 0..self.rows {
+            for rx in 0..rhs.cols {
+                let mut val = Default::default();
+                for lx in 0..self.cols {
+                    val += self.data[ly * self.cols + lx] * rhs.data[lx * rhs.cols + rx];
+                }
+
+                res.data[ly * rhs.cols + rx] = val;
+            }
+        }
+
+        res
+    }
+}
+
+impl<T: Copy + ops::AddAssign> DynMatrix<T> {
+    /// Performs addition with a matrix on the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have same dimensions.
+    pub fn add_matrix(&mut self, rhs: &DynMatrix<T>) {
+        assert!(
+            self.rows == rhs.rows && self.cols == rhs.cols,
+            "incompatible matrix dimensions"
+        );
+
+        for (cell, &other) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *cell += other;
+        }
+    }
+}
+
+impl<T> DynMatrix<T>
+where
+    T: Default + Copy + SampleUniform,
+{
+    /// Creates new `DynMatrix` with random values drawn from `rng`.
+    pub fn with_random<R: Rng + ?Sized>(
+        rows: usize,
+        cols: usize,
+        low: T,
+        high: T,
+        rng: &mut R,
+    ) -> Self {
+        let mut res = DynMatrix::new(rows, cols);
+        for cell in res.data.iter_mut() {
+            *cell = rng.gen_range(low, high);
+        }
+
+        res
+    }
+
+    /// Crossovers two matrices at one random position producing a new matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have same dimensions.
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &DynMatrix<T>, rng: &mut R) -> Self {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "incompatible matrix dimensions"
+        );
+
+        let pr: usize = rng.gen_range(0, self.rows);
+        let pc: usize = rng.gen_range(0, self.cols);
+
+        let mut res = self.clone();
+
+        for y in pr..self.rows {
+            for x in pc..self.cols {
+                res.data[y * self.cols + x] = other.data[y * self.cols + x];
+            }
+        }
+
+        res
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(data: [[T; C]; R]) -> Self {
+        Self { data }
+    }
+}
+
+impl<T: Clone, const R: usize, const C: usize> From<&Matrix<T, R, C>> for Matrix<T, R, C> {
+    #[inline]
+    fn from(matrix: &Matrix<T, R, C>) -> Self {
+        matrix.clone()
+    }
+}
+
+impl<T: Serialize, const R: usize, const C: usize> Serialize for Matrix<T, R, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.data.iter().map(|row| &row[..]))
+    }
+}
+
+impl<'de, T, const R: usize, const C: usize> Deserialize<'de> for Matrix<T, R, C>
+where
+    T: Deserialize<'de> + Copy + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        if rows.len() != R || rows.iter().any(|row| row.len() != C) {
+            return Err(de::Error::custom(format!("expected a {}x{} matrix", R, C)));
+        }
+
+        let mut res = Matrix::new();
+        for (y, row) in rows.iter().enumerate() {
+            res.data[y].copy_from_slice(row);
+        }
+
+        Ok(res)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for DynMatrix<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Raw<T> {
+            rows: usize,
+            cols: usize,
+            data: Vec<T>,
+        }
+
+        let Raw { rows, cols, data } = Raw::deserialize(deserializer)?;
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(de::Error::custom(format!(
+                "expected {} values in a {}x{} matrix, found {}",
+                rows.saturating_mul(cols),
+                rows,
+                cols,
+                data.len()
+            )));
+        }
+
+        Ok(Self { rows, cols, data })
+    }
 }
 
 impl<T, const R: usize, const C: usize> AsRef<[[T; C]; R]> for Matrix<T, R, C> {
@@ -223,6 +984,27 @@ impl<T, const R: usize, const C: usize> AsRef<[[T; C]; R]> for Matrix<T, R, C> {
     }
 }
 
+/// Indexes the matrix by `(row, column)`.
+///
+/// # Panics
+///
+/// Panics if the position is out of bounds.
+impl<T, const R: usize, const C: usize> ops::Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[row][col]
+    }
+}
+
+impl<T, const R: usize, const C: usize> ops::IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    #[inline]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[row][col]
+    }
+}
+
 impl<T, const R: usize, const C: usize> ops::AddAssign<&Matrix<T, R, C>> for Matrix<T, R, C>
 where
     T: Copy + ops::AddAssign,
@@ -246,6 +1028,29 @@ where
     }
 }
 
+impl<T, const R: usize, const C: usize> ops::SubAssign<&Matrix<T, R, C>> for Matrix<T, R, C>
+where
+    T: Copy + ops::SubAssign,
+{
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.sub_matrix(rhs);
+    }
+}
+
+impl<T, const R: usize, const C: usize> ops::Sub<&Matrix<T, R, C>> for Matrix<T, R, C>
+where
+    T: Copy + ops::SubAssign,
+{
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: &Self) -> Self::Output {
+        self.sub_matrix(rhs);
+        self
+    }
+}
+
 impl<T, const R: usize, const C: usize> ops::MulAssign<T> for Matrix<T, R, C>
 where
     T: Copy + ops::Mul<Output = T>,
@@ -269,14 +1074,13 @@ where
     }
 }
 
-impl<T, const R: usize, const C: usize, const OC: usize> ops::MulAssign<&Matrix<T, C, OC>>
-    for Matrix<T, R, C>
+impl<T, const R: usize, const C: usize> ops::MulAssign<&Matrix<T, C, C>> for Matrix<T, R, C>
 where
     T: Copy + Default + std::ops::Mul<Output = T> + std::ops::AddAssign,
 {
     #[inline]
-    fn mul_assign(&mut self, rhs: &Matrix<T, C, OC>) {
-        self.mul_matrix(rhs);
+    fn mul_assign(&mut self, rhs: &Matrix<T, C, C>) {
+        *self = self.mul_matrix(rhs);
     }
 }
 
@@ -318,9 +1122,612 @@ impl AABBf {
             && self.max.y > other.min.y
             && other.max.y > self.min.y
     }
+
+    /// Test whether the given point lies inside this box, borders included.
+    pub fn contains_point(&self, point: &Vector2f) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+
+    /// Returns the center of this box.
+    pub fn center(&self) -> Vector2f {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Returns the width and height of this box.
+    pub fn size(&self) -> Vector2f {
+        self.max - self.min
+    }
+
+    /// Returns the smallest box containing both this box and `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Vector2f::from_coords(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Vector2f::from_coords(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Returns the overlapping part of this box and `other`, or `None` if they do not intersect.
+    /// The size of the overlap is the penetration depth on each axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{AABBf, Vector2f};
+    ///
+    /// let player = AABBf {
+    ///     min: Vector2f::from_coords(0.0, 0.0),
+    ///     max: Vector2f::from_coords(25.0, 25.0),
+    /// };
+    ///
+    /// let floor = AABBf {
+    ///     min: Vector2f::from_coords(-100.0, 20.0),
+    ///     max: Vector2f::from_coords(100.0, 40.0),
+    /// };
+    ///
+    /// let depth = player.intersection(&floor).unwrap().size();
+    ///
+    /// assert!((depth.y - 5.0).abs() < 0.00001);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Self {
+            min: Vector2f::from_coords(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+            max: Vector2f::from_coords(self.max.x.min(other.max.x), self.max.y.min(other.max.y)),
+        })
+    }
+
+    /// Returns how this box touches `other`, or `None` if they do not intersect. The boxes are
+    /// separated along the axis with the smaller penetration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{AABBf, Vector2f};
+    ///
+    /// let player = AABBf {
+    ///     min: Vector2f::from_coords(0.0, 0.0),
+    ///     max: Vector2f::from_coords(25.0, 25.0),
+    /// };
+    ///
+    /// let floor = AABBf {
+    ///     min: Vector2f::from_coords(-100.0, 20.0),
+    ///     max: Vector2f::from_coords(100.0, 40.0),
+    /// };
+    ///
+    /// let contact = player.contact(&floor).unwrap();
+    ///
+    /// assert_eq!(contact.normal, Vector2f::from_coords(0.0, -1.0));
+    /// assert!((contact.depth - 5.0).abs() < 0.00001);
+    /// ```
+    pub fn contact(&self, other: &Self) -> Option<Contact> {
+        let overlap = self.intersection(other)?;
+        let depth = overlap.size();
+        let offset = self.center() - other.center();
+
+        let (normal, depth) = if depth.x < depth.y {
+            let sign = if offset.x < 0.0 { -1.0 } else { 1.0 };
+            (Vector2f::from_coords(sign, 0.0), depth.x)
+        } else {
+            let sign = if offset.y < 0.0 { -1.0 } else { 1.0 };
+            (Vector2f::from_coords(0.0, sign), depth.y)
+        };
+
+        Some(Contact {
+            normal,
+            depth,
+            point: overlap.center(),
+        })
+    }
+
+    /// Casts a ray from `origin` in the direction `dir` and returns where it enters this box,
+    /// unless that is further than `max_dist`. `dir` does not have to be normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{AABBf, Vector2f};
+    ///
+    /// let obstacle = AABBf {
+    ///     min: Vector2f::from_coords(300.0, 565.0),
+    ///     max: Vector2f::from_coords(325.0, 600.0),
+    /// };
+    ///
+    /// let eye = Vector2f::from_coords(125.0, 590.0);
+    /// let hit = obstacle
+    ///     .raycast(eye, Vector2f::from_coords(1.0, 0.0), 1000.0)
+    ///     .unwrap();
+    ///
+    /// assert!((hit.distance - 175.0).abs() < 0.00001);
+    /// assert!(obstacle.raycast(eye, Vector2f::from_coords(1.0, 0.0), 100.0).is_none());
+    /// ```
+    pub fn raycast(&self, origin: Vector2f, dir: Vector2f, max_dist: f32) -> Option<RayHit> {
+        let dir = dir.normalize();
+        if dir.length_squared() == 0.0 {
+            return None;
+        }
+
+        let origin_axes = [origin.x, origin.y];
+        let dir_axes = [dir.x, dir.y];
+        let min_axes = [self.min.x, self.min.y];
+        let max_axes = [self.max.x, self.max.y];
+
+        let mut near = 0.0;
+        let mut far = max_dist;
+        let mut normal_axes = [0.0; 2];
+
+        for axis in 0..2 {
+            let (o, d) = (origin_axes[axis], dir_axes[axis]);
+
+            if d == 0.0 {
+                if o < min_axes[axis] || o > max_axes[axis] {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let (mut t1, mut t2) = ((min_axes[axis] - o) / d, (max_axes[axis] - o) / d);
+            if t1 > t2 {
+                std::mem::swap(&mut t1, &mut t2);
+            }
+
+            if t1 > near {
+                near = t1;
+                normal_axes = [0.0; 2];
+                normal_axes[axis] = -d.signum();
+            }
+
+            far = far.min(t2);
+            if near > far {
+                return None;
+            }
+        }
+
+        Some(RayHit {
+            distance: near,
+            point: origin + dir * near,
+            normal: Vector2f::from_coords(normal_axes[0], normal_axes[1]),
+        })
+    }
+
+    /// Returns the point of this box closest to `point`, which is `point` itself if it lies
+    /// inside.
+    pub fn closest_point(&self, point: &Vector2f) -> Vector2f {
+        Vector2f::from_coords(
+            point.x.clamp(self.min.x, self.max.x),
+            point.y.clamp(self.min.y, self.max.y),
+        )
+    }
+
+    /// Returns this box grown by `margin` on every side. Negative margin shrinks it.
+    pub fn expand(&self, margin: f32) -> Self {
+        let margin = Vector2f::from_coords(margin, margin);
+
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// Tests whether this box hits `other` while moving by `displacement`, e.g. the velocity
+    /// multiplied by the duration of one step. Unlike testing [`intersects`](#method.intersects)
+    /// at the end of the movement, fast boxes cannot tunnel through each other.
+    ///
+    /// If both boxes move, pass the displacement of this box relative to `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{AABBf, Vector2f};
+    ///
+    /// let bullet = AABBf {
+    ///     min: Vector2f::from_coords(0.0, 0.0),
+    ///     max: Vector2f::from_coords(5.0, 5.0),
+    /// };
+    ///
+    /// let wall = AABBf {
+    ///     min: Vector2f::from_coords(50.0, -10.0),
+    ///     max: Vector2f::from_coords(55.0, 10.0),
+    /// };
+    ///
+    /// // The bullet would be far behind the wall at the end of the step.
+    /// let hit = bullet.sweep(Vector2f::from_coords(90.0, 0.0), &wall).unwrap();
+    ///
+    /// assert!((hit.time - 0.5).abs() < 0.00001);
+    /// assert_eq!(hit.normal, Vector2f::from_coords(-1.0, 0.0));
+    /// ```
+    pub fn sweep(&self, displacement: Vector2f, other: &Self) -> Option<SweepHit> {
+        // Returns the interval of the movement during which the boxes overlap on one axis.
+        let axis = |min: f32, max: f32, other_min: f32, other_max: f32, d: f32| {
+            if d == 0.0 {
+                if max <= other_min || min >= other_max {
+                    None
+                } else {
+                    Some((f32::NEG_INFINITY, f32::INFINITY))
+                }
+            } else if d > 0.0 {
+                Some(((other_min - max) / d, (other_max - min) / d))
+            } else {
+                Some(((other_max - min) / d, (other_min - max) / d))
+            }
+        };
+
+        let (entry_x, exit_x) = axis(
+            self.min.x,
+            self.max.x,
+            other.min.x,
+            other.max.x,
+            displacement.x,
+        )?;
+        let (entry_y, exit_y) = axis(
+            self.min.y,
+            self.max.y,
+            other.min.y,
+            other.max.y,
+            displacement.y,
+        )?;
+
+        let entry = entry_x.max(entry_y);
+        let exit = exit_x.min(exit_y);
+
+        if entry >= exit || exit <= 0.0 || entry > 1.0 {
+            return None;
+        }
+
+        if entry < 0.0 {
+            return Some(SweepHit {
+                time: 0.0,
+                normal: Vector2f::new(),
+            });
+        }
+
+        let normal = if entry_x > entry_y {
+            Vector2f::from_coords(-displacement.x.signum(), 0.0)
+        } else {
+            Vector2f::from_coords(0.0, -displacement.y.signum())
+        };
+
+        Some(SweepHit {
+            time: entry,
+            normal,
+        })
+    }
+
+    /// Returns this box moved by `offset`.
+    pub fn translate(&self, offset: Vector2f) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+impl Circlef {
+    /// Test whether two circles intersect.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let radius = self.radius + other.radius;
+
+        (self.center - other.center).length_squared() < radius * radius
+    }
+
+    /// Test whether this circle intersects the given box.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{AABBf, Circlef, Vector2f};
+    ///
+    /// let aabb = AABBf {
+    ///     min: Vector2f::from_coords(0.0, 0.0),
+    ///     max: Vector2f::from_coords(10.0, 10.0),
+    /// };
+    ///
+    /// // Close to the corner, but not touching it.
+    /// let circle = Circlef {
+    ///     center: Vector2f::from_coords(13.0, 13.0),
+    ///     radius: 4.0,
+    /// };
+    ///
+    /// assert!(!circle.intersects_aabb(&aabb));
+    /// ```
+    pub fn intersects_aabb(&self, aabb: &AABBf) -> bool {
+        let closest = aabb.closest_point(&self.center);
+
+        (self.center - closest).length_squared() < self.radius * self.radius
+    }
+
+    /// Returns the smallest box containing this circle.
+    pub fn bounding_box(&self) -> AABBf {
+        AABBf {
+            min: self.center,
+            max: self.center,
+        }
+        .expand(self.radius)
+    }
+}
+
+impl Capsulef {
+    /// Test whether this capsule intersects the given circle.
+    pub fn intersects_circle(&self, circle: &Circlef) -> bool {
+        let radius = self.radius + circle.radius;
+
+        segment_distance_to_point(self.start, self.end, circle.center) < radius
+    }
+
+    /// Test whether this capsule intersects the given box.
+    pub fn intersects_aabb(&self, aabb: &AABBf) -> bool {
+        segment_distance_to_aabb(self.start, self.end, aabb) < self.radius
+    }
+
+    /// Test whether two capsules intersect.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let distance = segments_distance(self.start, self.end, other.start, other.end);
+
+        distance < self.radius + other.radius
+    }
+
+    /// Returns the smallest box containing this capsule.
+    pub fn bounding_box(&self) -> AABBf {
+        AABBf {
+            min: self.start,
+            max: self.start,
+        }
+        .union(&AABBf {
+            min: self.end,
+            max: self.end,
+        })
+        .expand(self.radius)
+    }
+}
+
+impl Shape {
+    /// Test whether two shapes intersect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{Capsulef, Circlef, Shape, Vector2f};
+    ///
+    /// let capsule = Shape::Capsule(Capsulef {
+    ///     start: Vector2f::from_coords(0.0, 0.0),
+    ///     end: Vector2f::from_coords(0.0, 20.0),
+    ///     radius: 5.0,
+    /// });
+    ///
+    /// let circle = Shape::Circle(Circlef {
+    ///     center: Vector2f::from_coords(8.0, 10.0),
+    ///     radius: 4.0,
+    /// });
+    ///
+    /// assert!(capsule.intersects(&circle));
+    /// assert!(circle.intersects(&capsule));
+    /// ```
+    pub fn intersects(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Shape::Aabb(a), Shape::Aabb(b)) => a.intersects(b),
+            (Shape::Circle(a), Shape::Circle(b)) => a.intersects(b),
+            (Shape::Capsule(a), Shape::Capsule(b)) => a.intersects(b),
+            (Shape::Circle(c), Shape::Aabb(b)) | (Shape::Aabb(b), Shape::Circle(c)) => {
+                c.intersects_aabb(b)
+            }
+            (Shape::Capsule(c), Shape::Aabb(b)) | (Shape::Aabb(b), Shape::Capsule(c)) => {
+                c.intersects_aabb(b)
+            }
+            (Shape::Capsule(c), Shape::Circle(b)) | (Shape::Circle(b), Shape::Capsule(c)) => {
+                c.intersects_circle(b)
+            }
+            (Shape::Obb(a), Shape::Obb(b)) => a.intersects(b),
+            (Shape::Obb(o), Shape::Aabb(b)) | (Shape::Aabb(b), Shape::Obb(o)) => {
+                o.intersects_aabb(b)
+            }
+            (Shape::Obb(o), Shape::Circle(c)) | (Shape::Circle(c), Shape::Obb(o)) => {
+                o.intersects_circle(c)
+            }
+            (Shape::Obb(o), Shape::Capsule(c)) | (Shape::Capsule(c), Shape::Obb(o)) => {
+                o.intersects_capsule(c)
+            }
+        }
+    }
+
+    /// Returns the smallest box containing this shape.
+    pub fn bounding_box(&self) -> AABBf {
+        match self {
+            Shape::Aabb(aabb) => *aabb,
+            Shape::Circle(circle) => circle.bounding_box(),
+            Shape::Capsule(capsule) => capsule.bounding_box(),
+            Shape::Obb(obb) => obb.bounding_box(),
+        }
+    }
+}
+
+fn cross(a: Vector2f, b: Vector2f) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+impl OBBf {
+    /// Creates an unrotated box covering `aabb`.
+    pub fn from_aabb(aabb: &AABBf) -> Self {
+        Self {
+            center: aabb.center(),
+            half_size: aabb.size() / 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Returns the corners of this box.
+    pub fn corners(&self) -> [Vector2f; 4] {
+        let (x, y) = (self.half_size.x, self.half_size.y);
+
+        [(-x, -y), (x, -y), (x, y), (-x, y)]
+            .map(|(x, y)| self.center + Vector2f::from_coords(x, y).rotate(self.rotation))
+    }
+
+    /// Test whether two boxes intersect, using the separating axis theorem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::{OBBf, Vector2f};
+    ///
+    /// let a = OBBf {
+    ///     center: Vector2f::from_coords(0.0, 0.0),
+    ///     half_size: Vector2f::from_coords(10.0, 1.0),
+    ///     rotation: std::f32::consts::FRAC_PI_4,
+    /// };
+    /// let b = OBBf {
+    ///     center: Vector2f::from_coords(8.0, 0.0),
+    ///     half_size: Vector2f::from_coords(1.0, 1.0),
+    ///     rotation: 0.0,
+    /// };
+    ///
+    /// // The diagonal bar passes above the right box.
+    /// assert!(!a.intersects(&b));
+    /// ```
+    pub fn intersects(&self, other: &Self) -> bool {
+        let (corners, other_corners) = (self.corners(), other.corners());
+        let axes = [
+            Vector2f::from_coords(1.0, 0.0).rotate(self.rotation),
+            Vector2f::from_coords(0.0, 1.0).rotate(self.rotation),
+            Vector2f::from_coords(1.0, 0.0).rotate(other.rotation),
+            Vector2f::from_coords(0.0, 1.0).rotate(other.rotation),
+        ];
+
+        axes.iter().all(|axis| {
+            let (min, max) = project(&corners, axis);
+            let (other_min, other_max) = project(&other_corners, axis);
+
+            max > other_min && other_max > min
+        })
+    }
+
+    /// Test whether this box intersects the given axis-aligned box.
+    pub fn intersects_aabb(&self, aabb: &AABBf) -> bool {
+        self.intersects(&Self::from_aabb(aabb))
+    }
+
+    /// Test whether this box intersects the given circle.
+    pub fn intersects_circle(&self, circle: &Circlef) -> bool {
+        let local = Circlef {
+            center: self.local_point(circle.center),
+            radius: circle.radius,
+        };
+
+        local.intersects_aabb(&self.local_aabb())
+    }
+
+    /// Test whether this box intersects the given capsule.
+    pub fn intersects_capsule(&self, capsule: &Capsulef) -> bool {
+        let (start, end) = (
+            self.local_point(capsule.start),
+            self.local_point(capsule.end),
+        );
+
+        segment_distance_to_aabb(start, end, &self.local_aabb()) < capsule.radius
+    }
+
+    /// Returns the smallest axis-aligned box containing this box.
+    pub fn bounding_box(&self) -> AABBf {
+        let corners = self.corners();
+        let mut res = AABBf {
+            min: corners[0],
+            max: corners[0],
+        };
+        for corner in corners.iter().skip(1) {
+            res = res.union(&AABBf {
+                min: *corner,
+                max: *corner,
+            });
+        }
+
+        res
+    }
+
+    // Transforms `point` into the frame of this box, in which the box is axis-aligned and
+    // centered at the origin.
+    fn local_point(&self, point: Vector2f) -> Vector2f {
+        (point - self.center).rotate(-self.rotation)
+    }
+
+    fn local_aabb(&self) -> AABBf {
+        AABBf {
+            min: Vector2f::new() - self.half_size,
+            max: self.half_size,
+        }
+    }
+}
+
+// Returns the interval covered by projecting `points` onto `axis`.
+fn project(points: &[Vector2f], axis: &Vector2f) -> (f32, f32) {
+    points
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+            let p = point.dot(axis);
+            (min.min(p), max.max(p))
+        })
+}
+
+fn segment_distance_to_point(start: Vector2f, end: Vector2f, point: Vector2f) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return start.distance_to(&point);
+    }
+
+    let t = ((point - start).dot(&segment) / length_squared).clamp(0.0, 1.0);
+
+    start.lerp(&end, t).distance_to(&point)
+}
+
+fn segments_distance(a1: Vector2f, a2: Vector2f, b1: Vector2f, b2: Vector2f) -> f32 {
+    let d1 = cross(b2 - b1, a1 - b1);
+    let d2 = cross(b2 - b1, a2 - b1);
+    let d3 = cross(a2 - a1, b1 - a1);
+    let d4 = cross(a2 - a1, b2 - a1);
+
+    // The segments cross each other.
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return 0.0;
+    }
+
+    // Otherwise the closest points include an end point of one of the segments.
+    segment_distance_to_point(b1, b2, a1)
+        .min(segment_distance_to_point(b1, b2, a2))
+        .min(segment_distance_to_point(a1, a2, b1))
+        .min(segment_distance_to_point(a1, a2, b2))
+}
+
+fn segment_distance_to_aabb(start: Vector2f, end: Vector2f, aabb: &AABBf) -> f32 {
+    let length = start.distance_to(&end);
+    if aabb.contains_point(&start) || aabb.raycast(start, end - start, length).is_some() {
+        return 0.0;
+    }
+
+    // Otherwise the closest points include an end point of the segment or a corner of the box.
+    let corners = [
+        aabb.min,
+        aabb.max,
+        Vector2f::from_coords(aabb.min.x, aabb.max.y),
+        Vector2f::from_coords(aabb.max.x, aabb.min.y),
+    ];
+
+    corners
+        .iter()
+        .map(|&corner| segment_distance_to_point(start, end, corner))
+        .chain(
+            [start, end]
+                .iter()
+                .map(|p| p.distance_to(&aabb.closest_point(p))),
+        )
+        .fold(f32::INFINITY, f32::min)
 }
 
-impl ops::Add<Vector2f> for Vector2f {
+impl<T: ops::Add<Output = T>> ops::Add<Vector2<T>> for Vector2<T> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
@@ -331,17 +1738,46 @@ impl ops::Add<Vector2f> for Vector2f {
     }
 }
 
-impl ops::AddAssign<Vector2f> for Vector2f {
+impl<T: ops::AddAssign> ops::AddAssign<Vector2<T>> for Vector2<T> {
     fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
         self.y += rhs.y;
     }
 }
 
-impl ops::Mul<f32> for Vector2f {
+impl<T: ops::Sub<Output = T>> ops::Sub<Vector2<T>> for Vector2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl<T: ops::SubAssign> ops::SubAssign<Vector2<T>> for Vector2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: ops::Neg<Output = T>> ops::Neg for Vector2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Vector2<T> {
     type Output = Self;
 
-    fn mul(self, rhs: f32) -> Self {
+    fn mul(self, rhs: T) -> Self {
         Self {
             x: self.x * rhs,
             y: self.y * rhs,
@@ -349,6 +1785,31 @@ impl ops::Mul<f32> for Vector2f {
     }
 }
 
+impl<T: Copy + ops::MulAssign> ops::MulAssign<T> for Vector2<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl<T: Copy + ops::Div<Output = T>> ops::Div<T> for Vector2<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl<T: Copy + ops::DivAssign> ops::DivAssign<T> for Vector2<T> {
+    fn div_assign(&mut self, rhs: T) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,6 +1828,26 @@ mod tests {
             .all(|(r1, r2)| r1.iter().zip(r2.iter()).all(|(&a, &b)| f32_eq(a, b)))
     }
 
+    #[test]
+    fn test_crossover_strategies() {
+        let a = Matrix::from([[0.0, 0.0], [0.0, 0.0]]);
+        let b = Matrix::from([[1.0, 1.0], [1.0, 1.0]]);
+        let mut rng = rand::thread_rng();
+
+        let child = a.crossover_with(&b, CrossoverStrategy::Uniform, &mut rng);
+        assert!(child.iter().all(|&w| w == 0.0 || w == 1.0));
+
+        // Both weights of a column come from the same parent.
+        let child = a.crossover_with(&b, CrossoverStrategy::Neuron, &mut rng);
+        for col in child.cols() {
+            let col: Vec<f32> = col.copied().collect();
+            assert_eq!(col[0], col[1]);
+        }
+
+        let child = a.crossover_with(&b, CrossoverStrategy::Average, &mut rng);
+        assert!(matrix_eq(&child, &Matrix::with_val(0.5)));
+    }
+
     #[test]
     fn test_vec_add() {
         let a = Vector2f::from_coords(1.0, 1.0);
@@ -387,6 +1868,61 @@ mod tests {
         assert!(f32_eq(a.x, 3.0) && f32_eq(a.y, 4.0));
     }
 
+    #[test]
+    fn test_vec_sub_div() {
+        let a = Vector2f::from_coords(5.0, 1.0);
+        let b = Vector2f::from_coords(1.0, 3.0);
+
+        let res = -((a - b) / 2.0);
+
+        assert!(f32_eq(res.x, -2.0) && f32_eq(res.y, 1.0));
+    }
+
+    #[test]
+    fn test_vec_assign_ops() {
+        let mut a = Vector2f::from_coords(4.0, 2.0);
+
+        a -= Vector2f::from_coords(1.0, 1.0);
+        a /= 2.0;
+        a = 3.0 * a;
+
+        assert!(f32_eq(a.x, 4.5) && f32_eq(a.y, 1.5));
+    }
+
+    #[test]
+    fn test_vec_conversions() {
+        let a = Vector2f::from_coords(12.7, -3.2);
+
+        assert_eq!(a.as_vector2i(), Vector2i::from_coords(12, -3));
+        assert_eq!(a.as_vector2u(), Vector2u::from_coords(12, 0));
+        assert_eq!(
+            Vector2u::from_coords(4, 5).cast::<f64>(),
+            Vector2d::from_coords(4.0, 5.0)
+        );
+        assert_eq!(
+            Vector2i::from_coords(1, 2) * 3 - Vector2i::from_coords(1, 1),
+            Vector2i::from_coords(2, 5)
+        );
+    }
+
+    #[test]
+    fn test_vec_normalize() {
+        let a = Vector2f::from_coords(-3.0, 4.0).normalize();
+
+        assert!(f32_eq(a.length(), 1.0));
+        assert!(f32_eq(a.x, -0.6) && f32_eq(a.y, 0.8));
+        assert!(f32_eq(Vector2f::new().normalize().length(), 0.0));
+    }
+
+    #[test]
+    fn test_vec_distance() {
+        let a = Vector2f::from_coords(1.0, 1.0);
+        let b = Vector2f::from_coords(4.0, 5.0);
+
+        assert!(f32_eq(a.distance_to(&b), 5.0));
+        assert!(f32_eq(a.dot(&b), 9.0));
+    }
+
     #[test]
     fn test_matrix_mul1() {
         let a = Matrix::from([[0.0, 5.0, 1.5], [2.0, 2.5, -0.5]]);
@@ -425,6 +1961,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_matrix_mul_f32_matches_f64() {
+        let mut rng = rand::thread_rng();
+        let a: Matrix<f32, 3, 6> = Matrix::with_random(-1.0, 1.0, &mut rng);
+        let b: Matrix<f32, 6, 19> = Matrix::with_random(-1.0, 1.0, &mut rng);
+
+        // `f64` matrices are multiplied by the generic loop.
+        let mut a64: Matrix<f64, 3, 6> = Matrix::new();
+        a64.iter_mut()
+            .zip(a.iter())
+            .for_each(|(x, &y)| *x = y.into());
+        let mut b64: Matrix<f64, 6, 19> = Matrix::new();
+        b64.iter_mut()
+            .zip(b.iter())
+            .for_each(|(x, &y)| *x = y.into());
+
+        let res = a.mul_matrix_f32(&b);
+        let expected = a64 * &b64;
+
+        assert!(
+            res.iter()
+                .zip(expected.iter())
+                .all(|(&x, &y)| f32_eq(x, y as f32)),
+            "expected: {:?}, got: {:?}",
+            expected,
+            res
+        );
+    }
+
+    #[test]
+    fn test_matrix_mul_assign() {
+        let mut a = Matrix::from([[1.0, 2.0], [3.0, 4.0]]);
+        let b = Matrix::from([[0.0, 1.0], [1.0, 0.0]]);
+
+        let expected = Matrix::from([[2.0, 1.0], [4.0, 3.0]]);
+
+        a *= &b;
+
+        assert!(
+            matrix_eq(&expected, &a),
+            "expected: {:?}, got: {:?}",
+            expected,
+            a
+        );
+    }
+
     #[test]
     fn test_matrix_mul_scalar() {
         let mut a = Matrix::from([[2.3, 1.4, 4.5], [6.8, 3.1, 2.55]]);
@@ -457,6 +2039,165 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_matrix_sub() {
+        let a = Matrix::from([[1.2, 4.4, 1.5], [0.8, 8.1, 8.5]]);
+        let b = Matrix::with_val(1.0);
+
+        let expected = Matrix::from([[0.2, 3.4, 0.5], [-0.2, 7.1, 7.5]]);
+
+        let res = a - &b;
+
+        assert!(
+            matrix_eq(&expected, &res),
+            "expected: {:?}, got: {:?}",
+            expected,
+            res
+        );
+    }
+
+    #[test]
+    fn test_matrix_mul_elementwise() {
+        let mut a = Matrix::from([[1.0, 2.0], [3.0, 4.0]]);
+        let b = Matrix::from([[0.5, -1.0], [2.0, 0.0]]);
+
+        let expected = Matrix::from([[0.5, -2.0], [6.0, 0.0]]);
+
+        a.mul_elementwise(&b);
+
+        assert!(
+            matrix_eq(&expected, &a),
+            "expected: {:?}, got: {:?}",
+            expected,
+            a
+        );
+    }
+
+    #[test]
+    fn test_matrix_index() {
+        let mut a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        a[(1, 2)] = 7.0;
+        *a.get_mut(0, 1).unwrap() += 1.0;
+
+        assert!(f32_eq(a[(1, 2)], 7.0));
+        assert!(f32_eq(a[(0, 1)], 3.0));
+        assert!(a.get(0, 3).is_none());
+        assert!(a.get_mut(2, 0).is_none());
+    }
+
+    #[test]
+    fn test_matrix_iter() {
+        let mut a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        for cell in a.iter_mut() {
+            *cell *= 2.0;
+        }
+
+        assert!(f32_eq(a.iter().sum(), 42.0));
+        assert_eq!(a.rows().count(), 2);
+        assert_eq!(a.cols().count(), 3);
+
+        let last_col: Vec<f32> = a.cols().last().unwrap().copied().collect();
+        assert!(f32_eq(last_col[0], 6.0) && f32_eq(last_col[1], 12.0));
+    }
+
+    #[test]
+    fn test_matrix_transpose_twice() {
+        let a = Matrix::from([[1.2, 4.4, 1.5], [0.8, 8.1, 8.5]]);
+
+        let res = a.transpose().transpose();
+
+        assert!(matrix_eq(&a, &res), "expected: {:?}, got: {:?}", a, res);
+    }
+
+    #[test]
+    fn test_dyn_matrix_mul() {
+        let mut a = DynMatrix::new(2, 3);
+        a.data = vec![0.0, 5.0, 1.5, 2.0, 2.5, -0.5];
+        let mut b = DynMatrix::new(3, 2);
+        b.data = vec![0.0, 5.0, 2.0, 2.5, 1.0, -2.5];
+
+        let expected = [11.5, 8.75, 4.5, 17.5];
+
+        let res = a.mul_matrix(&b);
+
+        assert_eq!((res.rows(), res.cols()), (2, 2));
+        assert!(
+            res.as_slice()
+                .iter()
+                .zip(expected.iter())
+                .all(|(&a, &b)| f32_eq(a, b)),
+            "expected: {:?}, got: {:?}",
+            expected,
+            res
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dyn_matrix_mul_mismatch() {
+        let a: DynMatrix<f32> = DynMatrix::new(2, 3);
+        let b = DynMatrix::new(2, 3);
+
+        a.mul_matrix(&b);
+    }
+
+    #[test]
+    fn test_dyn_matrix_add() {
+        let mut a = DynMatrix::from_row(&[1.2, 4.4, 1.5]);
+        let b = DynMatrix::with_val(1, 3, 1.0);
+
+        a.add_matrix(&b);
+
+        assert!(f32_eq(a.get(0, 0), 2.2) && f32_eq(a.get(0, 1), 5.4) && f32_eq(a.get(0, 2), 2.5));
+    }
+
+    #[test]
+    fn test_seeded_random_is_deterministic() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng1 = StdRng::seed_from_u64(42);
+        let mut rng2 = StdRng::seed_from_u64(42);
+
+        let mut a: Matrix<f32, 3, 4> = Matrix::with_random(-1.0, 1.0, &mut rng1);
+        let mut b: Matrix<f32, 3, 4> = Matrix::with_random(-1.0, 1.0, &mut rng2);
+        let config = MutationConfig {
+            rate: 0.5,
+            ..Default::default()
+        };
+        mutate_matrixf(&mut a, &config, &mut rng1);
+        mutate_matrixf(&mut b, &config, &mut rng2);
+
+        assert!(matrix_eq(&a, &b));
+    }
+
+    #[test]
+    fn test_matrix_serde() {
+        let a = Matrix::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        let json = serde_json::to_string(&a).unwrap();
+        let b: Matrix<f32, 2, 3> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, "[[1.0,2.0,3.0],[4.0,5.0,6.0]]");
+        assert!(matrix_eq(&a, &b));
+        assert!(serde_json::from_str::<Matrix<f32, 3, 2>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_dyn_matrix_serde() {
+        let a = DynMatrix::from_row(&[1.0, 2.0, 3.0]);
+
+        let json = serde_json::to_string(&a).unwrap();
+        let b: DynMatrix<f32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(a.as_slice(), b.as_slice());
+        assert!(
+            serde_json::from_str::<DynMatrix<f32>>(r#"{"rows":2,"cols":3,"data":[1.0]}"#).is_err()
+        );
+    }
+
     #[test]
     fn test_intersection() {
         let left = AABBf {
@@ -487,8 +2228,231 @@ mod tests {
         assert!(!left.intersects(&right));
     }
 
+    #[test]
+    fn test_aabb_union_and_intersection() {
+        let left = AABBf {
+            min: Vector2f::from_coords(0.0, 0.0),
+            max: Vector2f::from_coords(20.0, 10.0),
+        };
+
+        let right = left.translate(Vector2f::from_coords(15.0, 5.0));
+
+        let union = left.union(&right);
+        assert_eq!(union.size(), Vector2f::from_coords(35.0, 15.0));
+
+        let overlap = left.intersection(&right).unwrap();
+        assert_eq!(overlap.size(), Vector2f::from_coords(5.0, 5.0));
+        assert_eq!(overlap.center(), Vector2f::from_coords(17.5, 7.5));
+
+        let far = left.translate(Vector2f::from_coords(100.0, 0.0));
+        assert_eq!(left.intersection(&far), None);
+    }
+
+    #[test]
+    fn test_aabb_contains_and_expand() {
+        let aabb = AABBf {
+            min: Vector2f::from_coords(0.0, 0.0),
+            max: Vector2f::from_coords(10.0, 10.0),
+        };
+        let point = Vector2f::from_coords(11.0, 5.0);
+
+        assert!(aabb.contains_point(&Vector2f::from_coords(10.0, 0.0)));
+        assert!(!aabb.contains_point(&point));
+        assert!(aabb.expand(1.0).contains_point(&point));
+    }
+
+    #[test]
+    fn test_contact() {
+        let wall = AABBf {
+            min: Vector2f::from_coords(0.0, 0.0),
+            max: Vector2f::from_coords(10.0, 100.0),
+        };
+        let aabb = AABBf {
+            min: Vector2f::from_coords(8.0, 40.0),
+            max: Vector2f::from_coords(18.0, 50.0),
+        };
+
+        let contact = aabb.contact(&wall).unwrap();
+        assert_eq!(contact.normal, Vector2f::from_coords(1.0, 0.0));
+        assert!(f32_eq(contact.depth, 2.0));
+        assert_eq!(contact.point, Vector2f::from_coords(9.0, 45.0));
+
+        let resolved = aabb.translate(contact.normal * contact.depth);
+        assert!(!resolved.intersects(&wall));
+    }
+
+    #[test]
+    fn test_raycast() {
+        let aabb = AABBf {
+            min: Vector2f::from_coords(10.0, 10.0),
+            max: Vector2f::from_coords(20.0, 20.0),
+        };
+
+        let hit = aabb
+            .raycast(Vector2f::new(), Vector2f::from_coords(1.0, 1.0), 100.0)
+            .unwrap();
+        assert!(f32_eq(hit.distance, 200.0_f32.sqrt()));
+        assert!(f32_eq(hit.point.x, 10.0) && f32_eq(hit.point.y, 10.0));
+
+        let hit = aabb
+            .raycast(
+                Vector2f::from_coords(15.0, 30.0),
+                Vector2f::from_coords(0.0, -2.0),
+                100.0,
+            )
+            .unwrap();
+        assert!(f32_eq(hit.distance, 10.0));
+        assert_eq!(hit.normal, Vector2f::from_coords(0.0, 1.0));
+
+        let inside = aabb.center();
+        let hit = aabb.raycast(inside, Vector2f::from_coords(1.0, 0.0), 100.0);
+        assert_eq!(hit.map(|hit| hit.distance), Some(0.0));
+
+        let behind = Vector2f::from_coords(30.0, 15.0);
+        assert!(aabb
+            .raycast(behind, Vector2f::from_coords(1.0, 0.0), 100.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_sweep() {
+        let player = AABBf {
+            min: Vector2f::from_coords(100.0, 0.0),
+            max: Vector2f::from_coords(125.0, 25.0),
+        };
+        let obstacle = player.translate(Vector2f::from_coords(60.0, 0.0));
+
+        // The obstacle passes the whole player within one step.
+        let hit = player
+            .sweep(Vector2f::from_coords(100.0, 0.0), &obstacle)
+            .unwrap();
+        assert!(f32_eq(hit.time, 0.35));
+        assert_eq!(hit.normal, Vector2f::from_coords(-1.0, 0.0));
+
+        assert_eq!(
+            player.sweep(Vector2f::from_coords(20.0, 0.0), &obstacle),
+            None
+        );
+        assert_eq!(
+            player.sweep(Vector2f::from_coords(0.0, 100.0), &obstacle),
+            None
+        );
+
+        let overlap = player.translate(Vector2f::from_coords(10.0, 10.0));
+        assert_eq!(
+            player.sweep(Vector2f::new(), &overlap).map(|hit| hit.time),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_circle_intersections() {
+        let a = Circlef {
+            center: Vector2f::new(),
+            radius: 5.0,
+        };
+        let b = Circlef {
+            center: Vector2f::from_coords(6.0, 8.0),
+            radius: 5.5,
+        };
+        let aabb = AABBf {
+            min: Vector2f::from_coords(4.0, -1.0),
+            max: Vector2f::from_coords(10.0, 1.0),
+        };
+
+        assert!(a.intersects(&b));
+        assert!(a.intersects_aabb(&aabb));
+        assert!(!b.intersects_aabb(&aabb));
+        assert_eq!(b.bounding_box().min, Vector2f::from_coords(0.5, 2.5));
+    }
+
+    #[test]
+    fn test_obb_intersections() {
+        let obb = OBBf {
+            center: Vector2f::new(),
+            half_size: Vector2f::from_coords(10.0, 2.0),
+            rotation: std::f32::consts::FRAC_PI_2,
+        };
+        let aabb = AABBf {
+            min: Vector2f::from_coords(3.0, -1.0),
+            max: Vector2f::from_coords(5.0, 1.0),
+        };
+        let circle = Circlef {
+            center: Vector2f::from_coords(0.0, 11.0),
+            radius: 1.5,
+        };
+        let capsule = Capsulef {
+            start: Vector2f::from_coords(-5.0, 8.0),
+            end: Vector2f::from_coords(5.0, 8.0),
+            radius: 0.5,
+        };
+
+        // Rotated upright, the box no longer reaches the box on its right.
+        assert!(!obb.intersects_aabb(&aabb));
+        assert!(obb.intersects_circle(&circle));
+        assert!(obb.intersects_capsule(&capsule));
+        assert!(Shape::Obb(obb).intersects(&Shape::Circle(circle)));
+
+        let bb = obb.bounding_box();
+        assert!((bb.min.x + 2.0).abs() < 0.0001 && (bb.max.y - 10.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_capsule_intersections() {
+        let capsule = Capsulef {
+            start: Vector2f::new(),
+            end: Vector2f::from_coords(20.0, 0.0),
+            radius: 2.0,
+        };
+        let crossing = Capsulef {
+            start: Vector2f::from_coords(10.0, -10.0),
+            end: Vector2f::from_coords(10.0, 10.0),
+            radius: 0.5,
+        };
+        let parallel = Capsulef {
+            start: Vector2f::from_coords(0.0, 5.0),
+            end: Vector2f::from_coords(20.0, 5.0),
+            radius: 2.0,
+        };
+        let above = AABBf {
+            min: Vector2f::from_coords(5.0, 1.5),
+            max: Vector2f::from_coords(8.0, 4.0),
+        };
+        let far = above.translate(Vector2f::from_coords(0.0, 1.0));
+
+        assert!(capsule.intersects(&crossing));
+        assert!(!capsule.intersects(&parallel));
+        assert!(capsule.intersects_aabb(&above));
+        assert!(!capsule.intersects_aabb(&far));
+        assert!(Shape::Capsule(capsule).intersects(&Shape::Aabb(above)));
+    }
+
     #[test]
     fn test_sigmoid() {
         assert!(f32_eq(sigmoid(1.234), 0.7745179));
     }
+
+    #[test]
+    fn test_relu() {
+        assert!(f32_eq(relu(1.5), 1.5) && f32_eq(relu(-1.5), 0.0));
+        assert!(f32_eq(leaky_relu(1.5, 0.01), 1.5) && f32_eq(leaky_relu(-1.5, 0.01), -0.015));
+    }
+
+    #[test]
+    fn test_softmax() {
+        let mut a = Matrix::from([[1.0, 2.0, 3.0], [0.0, 0.0, 0.0]]);
+        let expected = Matrix::from([
+            [0.09003057, 0.24472847, 0.66524096],
+            [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0],
+        ]);
+
+        softmax(&mut a);
+
+        assert!(
+            matrix_eq(&expected, &a),
+            "expected: {:?}, got: {:?}",
+            expected,
+            a
+        );
+    }
 }
@@ -0,0 +1,153 @@
+//! Training metrics exposed in the Prometheus text format.
+//!
+//! This module is only available with the `metrics` feature.
+
+use log::{debug, info};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Longest time a client may take to send its request or receive the response.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shared training metrics. Updating them is cheap and can be done from the
+/// simulation thread while the endpoint is being scraped.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::metrics::Metrics;
+///
+/// let metrics = Metrics::new();
+/// metrics.set_generation(3);
+/// metrics.set_best_fitness(12.5);
+/// metrics.add_steps(10);
+///
+/// assert!(metrics.render().contains("dinai_generation 3"));
+/// ```
+#[derive(Debug)]
+pub struct Metrics {
+    generation: AtomicU64,
+    best_fitness: AtomicU32,
+    steps: AtomicU64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Creates new `Metrics` with every value set to zero.
+    pub fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            best_fitness: AtomicU32::new(0.0f32.to_bits()),
+            steps: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the current generation number.
+    pub fn set_generation(&self, generation: u64) {
+        self.generation.store(generation, Ordering::Relaxed);
+    }
+
+    /// Sets the best fitness of the last finished generation.
+    pub fn set_best_fitness(&self, fitness: f32) {
+        self.best_fitness
+            .store(fitness.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Adds `count` simulation steps to the step counter.
+    pub fn add_steps(&self, count: u64) {
+        self.steps.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format. Steps
+    /// are only exported as a counter, so that every scraper computes the
+    /// steps per second on its own, e.g. with `rate(dinai_steps_total[1m])`.
+    pub fn render(&self) -> String {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let best_fitness = f32::from_bits(self.best_fitness.load(Ordering::Relaxed));
+        let steps = self.steps.load(Ordering::Relaxed);
+
+        let mut out = String::new();
+        out.push_str("# HELP dinai_generation Current generation.\n");
+        out.push_str("# TYPE dinai_generation gauge\n");
+        out.push_str(&format!("dinai_generation {}\n", generation));
+        out.push_str("# HELP dinai_best_fitness Best fitness of the last generation.\n");
+        out.push_str("# TYPE dinai_best_fitness gauge\n");
+        out.push_str(&format!("dinai_best_fitness {}\n", best_fitness));
+        out.push_str("# HELP dinai_steps_total Simulation steps performed.\n");
+        out.push_str("# TYPE dinai_steps_total counter\n");
+        out.push_str(&format!("dinai_steps_total {}\n", steps));
+
+        if let Some(bytes) = resident_memory() {
+            out.push_str("# HELP dinai_resident_memory_bytes Resident memory size.\n");
+            out.push_str("# TYPE dinai_resident_memory_bytes gauge\n");
+            out.push_str(&format!("dinai_resident_memory_bytes {}\n", bytes));
+        }
+
+        out
+    }
+}
+
+/// Starts a background thread serving `metrics` over HTTP on the given
+/// address. Every request is answered with the rendered metrics regardless
+/// of its path.
+pub fn serve<A: ToSocketAddrs>(addr: A, metrics: Arc<Metrics>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    info!("Serving metrics on http://{}", listener.local_addr()?);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A misbehaving client must not bring the endpoint down.
+            if let Err(err) = respond(stream, &metrics) {
+                debug!("Failed to answer a metrics request: {}", err);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    // Requests are answered one after another, so a client that stops sending must not block
+    // the ones after it.
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    // Skip the request head, the response does not depend on it.
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+
+    let body = metrics.render();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )?;
+
+    stream.flush()
+}
+
+fn resident_memory() -> Option<u64> {
+    // Only available on Linux. The resident set size is given in kB, which
+    // unlike the pages of /proc/self/statm does not depend on the page size.
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(kb * 1024)
+}
@@ -1,63 +1,1012 @@
 //! Neural network using genetic algorithms.
 
-use crate::math::{self, Matrix};
+use crate::genetic::MutationConfig;
+use crate::math::{self, CrossoverStrategy, DynMatrix, Matrix};
+use crate::Error;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+/// An activation function applied to the neurons of a layer.
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Activation {
+    /// The logistic function, squashing values into (0, 1).
+    #[default]
+    Sigmoid,
+
+    /// Rectified linear unit, `max(0, x)`.
+    Relu,
+
+    /// Hyperbolic tangent, squashing values into (-1, 1).
+    Tanh,
+
+    /// Rectified linear unit which multiplies negative values by the given slope instead of
+    /// zeroing them.
+    LeakyRelu(f32),
+
+    /// Normalizes the whole layer into a probability distribution. Intended for the output layer.
+    Softmax,
+}
+
+impl Activation {
+    /// Applies this activation function to the given layer.
+    pub fn apply<const R: usize, const C: usize>(self, layer: &mut Matrix<f32, R, C>) {
+        match self {
+            Activation::Sigmoid => layer.apply(math::sigmoid),
+            Activation::Relu => layer.apply(math::relu),
+            Activation::Tanh => layer.apply(f32::tanh),
+            Activation::LeakyRelu(slope) => layer.apply(|x| math::leaky_relu(x, slope)),
+            Activation::Softmax => math::softmax(layer),
+        }
+    }
+
+    /// Returns the derivative of this activation function at every cell of `layer`, which holds
+    /// values this function produced. Softmax is differentiated cell by cell, ignoring how the
+    /// cells depend on each other.
+    pub fn derive<const R: usize, const C: usize>(
+        self,
+        layer: &Matrix<f32, R, C>,
+    ) -> Matrix<f32, R, C> {
+        let mut res = layer.clone();
+        match self {
+            Activation::Sigmoid | Activation::Softmax => res.apply(|y| y * (1.0 - y)),
+            Activation::Relu => res.apply(|y| if y > 0.0 { 1.0 } else { 0.0 }),
+            Activation::Tanh => res.apply(|y| 1.0 - y * y),
+            Activation::LeakyRelu(slope) => res.apply(|y| if y > 0.0 { 1.0 } else { slope }),
+        }
+
+        res
+    }
+}
 
 /// Simple neural network with fixed topology.
-#[derive(Debug, Clone, Default)]
-pub struct NeuralNetwork<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize> {
+///
+/// The network has `LAYERS` hidden layers, each with `HIDDEN` neurons. All of them use the sigmoid
+/// activation unless configured otherwise, and every hidden layer can use a different one.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::math::Matrix;
+/// use dinai::neuralnet::NeuralNetwork;
+///
+/// let nnet: NeuralNetwork<3, 4, 1, 2> = NeuralNetwork::new(&mut rand::thread_rng());
+/// let output = nnet.feed(&Matrix::from([[0.5, 0.1, 0.0]]));
+///
+/// assert!(output.as_ref()[0][0] > 0.0);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeuralNetwork<
+    const INPUTS: usize,
+    const HIDDEN: usize,
+    const OUTPUTS: usize,
+    const LAYERS: usize = 1,
+> {
     hidden_layer_in: Matrix<f32, INPUTS, HIDDEN>,
+
+    // Connections between consecutive hidden layers, there are `LAYERS - 1`
+    // of them.
+    #[serde(deserialize_with = "hidden_layers::<_, HIDDEN, LAYERS>")]
+    hidden_layers: Vec<Matrix<f32, HIDDEN, HIDDEN>>,
+
     hidden_layer_out: Matrix<f32, HIDDEN, OUTPUTS>,
+
+    // Biases of the hidden layers, one row per layer, and of the outputs.
+    // Networks saved before biases were learned used a constant bias of 1.
+    #[serde(default = "ones")]
+    hidden_biases: Matrix<f32, LAYERS, HIDDEN>,
+    #[serde(default = "ones")]
+    output_bias: Matrix<f32, 1, OUTPUTS>,
+
+    // Activation functions of the hidden layers, one per layer. Networks
+    // saved before the layers could differ have a single one for all of them.
+    #[serde(
+        alias = "hidden_activation",
+        default = "sigmoids",
+        serialize_with = "serialize_activations",
+        deserialize_with = "hidden_activations"
+    )]
+    hidden_activations: [Activation; LAYERS],
+    output_activation: Activation,
 }
 
-impl<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize>
-    NeuralNetwork<INPUTS, HIDDEN, OUTPUTS>
+impl<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize, const LAYERS: usize> Default
+    for NeuralNetwork<INPUTS, HIDDEN, OUTPUTS, LAYERS>
 {
-    /// Creates new `NeuralNetwork` according to input and output size.
-    pub fn new() -> Self {
+    fn default() -> Self {
+        Self {
+            hidden_layer_in: Matrix::new(),
+            hidden_layers: vec![Matrix::new(); LAYERS.saturating_sub(1)],
+            hidden_layer_out: Matrix::new(),
+            hidden_biases: Matrix::new(),
+            output_bias: Matrix::new(),
+            hidden_activations: [Activation::default(); LAYERS],
+            output_activation: Activation::default(),
+        }
+    }
+}
+
+impl<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize, const LAYERS: usize>
+    NeuralNetwork<INPUTS, HIDDEN, OUTPUTS, LAYERS>
+{
+    /// Creates new `NeuralNetwork` according to input and output size with weights drawn from
+    /// `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `LAYERS` is zero.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        assert!(LAYERS > 0, "at least one hidden layer is required");
+
         Self {
-            hidden_layer_in: Matrix::with_random(-1.0, 1.0),
-            hidden_layer_out: Matrix::with_random(-1.0, 1.0),
+            hidden_layer_in: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_layers: (1..LAYERS)
+                .map(|_| Matrix::with_random(-1.0, 1.0, rng))
+                .collect(),
+            hidden_layer_out: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_biases: Matrix::with_random(-1.0, 1.0, rng),
+            output_bias: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_activations: [Activation::default(); LAYERS],
+            output_activation: Activation::default(),
         }
     }
 
+    /// Sets the activation function of every hidden layer.
+    pub fn set_hidden_activation(&mut self, activation: Activation) {
+        self.hidden_activations = [activation; LAYERS];
+    }
+
+    /// Sets the activation function of the hidden layer `layer`, counted from the inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layer` is not less than `LAYERS`.
+    pub fn set_layer_activation(&mut self, layer: usize, activation: Activation) {
+        self.hidden_activations[layer] = activation;
+    }
+
+    /// Sets the activation function of the output layer.
+    pub fn set_output_activation(&mut self, activation: Activation) {
+        self.output_activation = activation;
+    }
+
+    /// Returns the activation functions of the hidden layers, counted from the inputs.
+    pub fn hidden_activations(&self) -> [Activation; LAYERS] {
+        self.hidden_activations
+    }
+
+    /// Returns the activation function of the output layer.
+    pub fn output_activation(&self) -> Activation {
+        self.output_activation
+    }
+
     /// Feeds the neural network with the input, producing an ouput matrix with only one column and
     /// as many rows as requested outputs.
     pub fn feed(&self, input: &Matrix<f32, 1, INPUTS>) -> Matrix<f32, 1, OUTPUTS> {
-        let mut a = input.clone() * &self.hidden_layer_in;
-        Self::add_bias(&mut a);
-        Self::activate(&mut a);
+        self.feed_batch(input)
+    }
+
+    /// Feeds the neural network with every row of `inputs` at once, producing one row of outputs
+    /// per input. This is faster than feeding the rows one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    /// use dinai::neuralnet::NeuralNetwork;
+    ///
+    /// let nnet: NeuralNetwork<2, 4, 1, 2> = NeuralNetwork::new(&mut rand::thread_rng());
+    /// let outputs = nnet.feed_batch(&Matrix::from([[0.5, 0.1], [-0.3, 0.8]]));
+    ///
+    /// assert_eq!(outputs.as_ref()[1], nnet.feed(&Matrix::from([[-0.3, 0.8]])).as_ref()[0]);
+    /// ```
+    pub fn feed_batch<const N: usize>(
+        &self,
+        inputs: &Matrix<f32, N, INPUTS>,
+    ) -> Matrix<f32, N, OUTPUTS> {
+        let mut a = inputs.mul_matrix_f32(&self.hidden_layer_in);
+        add_bias(&mut a, &self.hidden_biases.as_ref()[0]);
+        self.hidden_activations[0].apply(&mut a);
+
+        for (i, layer) in self.hidden_layers.iter().enumerate() {
+            a = a.mul_matrix_f32(layer);
+            add_bias(&mut a, &self.hidden_biases.as_ref()[i + 1]);
+            self.hidden_activations[i + 1].apply(&mut a);
+        }
 
-        let mut res = a * &self.hidden_layer_out;
-        Self::add_bias(&mut res);
-        Self::activate(&mut res);
+        let mut res = a.mul_matrix_f32(&self.hidden_layer_out);
+        add_bias(&mut res, &self.output_bias.as_ref()[0]);
+        self.output_activation.apply(&mut res);
 
         res
     }
 
-    /// Crossovers two neural networks in order to produce a new child.
-    pub fn crossover(&self, other: &Self) -> Self {
-        let hidden_layer_in = self.hidden_layer_in.crossover(&other.hidden_layer_in);
-        let hidden_layer_out = self.hidden_layer_out.crossover(&other.hidden_layer_out);
+    /// Feeds the neural network with the input like [`feed`], but also returns the output of every
+    /// hidden layer.
+    ///
+    /// [`feed`]: #method.feed
+    fn forward(
+        &self,
+        input: &Matrix<f32, 1, INPUTS>,
+    ) -> (Vec<Matrix<f32, 1, HIDDEN>>, Matrix<f32, 1, OUTPUTS>) {
+        let mut hidden = Vec::with_capacity(LAYERS);
+
+        let mut a = input.mul_matrix_f32(&self.hidden_layer_in);
+        add_bias(&mut a, &self.hidden_biases.as_ref()[0]);
+        self.hidden_activations[0].apply(&mut a);
+        hidden.push(a);
+
+        for (i, layer) in self.hidden_layers.iter().enumerate() {
+            let mut a = hidden[i].mul_matrix_f32(layer);
+            add_bias(&mut a, &self.hidden_biases.as_ref()[i + 1]);
+            self.hidden_activations[i + 1].apply(&mut a);
+            hidden.push(a);
+        }
+
+        let mut res = hidden[hidden.len() - 1].mul_matrix_f32(&self.hidden_layer_out);
+        res += &self.output_bias;
+        self.output_activation.apply(&mut res);
+
+        (hidden, res)
+    }
+
+    /// Performs one step of gradient descent on the squared error between the output for `input`
+    /// and `target`. Returns the mean squared error before the step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::math::Matrix;
+    /// use dinai::neuralnet::NeuralNetwork;
+    ///
+    /// let mut nnet: NeuralNetwork<2, 4, 1> = NeuralNetwork::new(&mut rand::thread_rng());
+    /// let input = Matrix::from([[1.0, 0.0]]);
+    /// let target = Matrix::from([[0.9]]);
+    ///
+    /// let first = nnet.backward(&input, &target, 0.5);
+    /// for _ in 0..100 {
+    ///     nnet.backward(&input, &target, 0.5);
+    /// }
+    ///
+    /// assert!(nnet.backward(&input, &target, 0.5) < first);
+    /// ```
+    pub fn backward(
+        &mut self,
+        input: &Matrix<f32, 1, INPUTS>,
+        target: &Matrix<f32, 1, OUTPUTS>,
+        learning_rate: f32,
+    ) -> f32 {
+        let (hidden, output) = self.forward(input);
+
+        let error = output.clone() - target;
+        let loss = error.iter().map(|e| e * e).sum::<f32>() / OUTPUTS as f32;
+
+        // Gradients of the error with respect to the inputs of the current layer's neurons,
+        // propagated from the output back to the first hidden layer. Deltas of a layer are
+        // computed before the weights leading to it are updated.
+        let mut delta = error;
+        delta.mul_elementwise(&self.output_activation.derive(&output));
+
+        let last = &hidden[hidden.len() - 1];
+        let mut prev_delta = delta.clone() * &self.hidden_layer_out.transpose();
+        prev_delta.mul_elementwise(&self.hidden_activations[LAYERS - 1].derive(last));
+        self.hidden_layer_out -= &((last.transpose() * &delta) * learning_rate);
+        self.output_bias -= &(delta * learning_rate);
+        let mut delta = prev_delta;
+
+        // Hidden layer `i` connects the outputs of hidden layers `i` and `i + 1`.
+        for (i, layer) in self.hidden_layers.iter_mut().enumerate().rev() {
+            let mut prev_delta = delta.clone() * &layer.transpose();
+            prev_delta.mul_elementwise(&self.hidden_activations[i].derive(&hidden[i]));
+            *layer -= &((hidden[i].transpose() * &delta) * learning_rate);
+            for (col, d) in delta.iter().enumerate() {
+                self.hidden_biases[(i + 1, col)] -= d * learning_rate;
+            }
+            delta = prev_delta;
+        }
+
+        self.hidden_layer_in -= &((input.transpose() * &delta) * learning_rate);
+        for (col, d) in delta.iter().enumerate() {
+            self.hidden_biases[(0, col)] -= d * learning_rate;
+        }
+
+        loss
+    }
+
+    /// Crossovers two neural networks in order to produce a new child. The child uses the
+    /// activation functions of `self`.
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self {
+        self.crossover_with(other, CrossoverStrategy::Block, rng)
+    }
+
+    /// Crossovers two neural networks combining every weight matrix as described by `strategy`.
+    /// The child uses the activation functions of `self`.
+    pub fn crossover_with<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        strategy: CrossoverStrategy,
+        rng: &mut R,
+    ) -> Self {
+        let hidden_layer_in =
+            self.hidden_layer_in
+                .crossover_with(&other.hidden_layer_in, strategy, rng);
+        let hidden_layers = self
+            .hidden_layers
+            .iter()
+            .zip(other.hidden_layers.iter())
+            .map(|(a, b)| a.crossover_with(b, strategy, rng))
+            .collect();
+        let hidden_layer_out =
+            self.hidden_layer_out
+                .crossover_with(&other.hidden_layer_out, strategy, rng);
+        let hidden_biases = self
+            .hidden_biases
+            .crossover_with(&other.hidden_biases, strategy, rng);
+        let output_bias = self
+            .output_bias
+            .crossover_with(&other.output_bias, strategy, rng);
 
         Self {
             hidden_layer_in,
+            hidden_layers,
             hidden_layer_out,
+            hidden_biases,
+            output_bias,
+            hidden_activations: self.hidden_activations,
+            output_activation: self.output_activation,
         }
     }
 
-    /// Randomly mutates weights.
-    pub fn mutate(&mut self) {
-        const PROBABILITY: f32 = 0.05;
-        math::mutate_matrixf(&mut self.hidden_layer_in, PROBABILITY);
-        math::mutate_matrixf(&mut self.hidden_layer_out, PROBABILITY);
+    /// Randomly mutates weights as described by `config`.
+    pub fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R) {
+        math::mutate_matrixf(&mut self.hidden_layer_in, config, rng);
+        for layer in self.hidden_layers.iter_mut() {
+            math::mutate_matrixf(layer, config, rng);
+        }
+        math::mutate_matrixf(&mut self.hidden_layer_out, config, rng);
+        math::mutate_matrixf(&mut self.hidden_biases, config, rng);
+        math::mutate_matrixf(&mut self.output_bias, config, rng);
     }
 
-    fn add_bias<const R: usize, const C: usize>(layer: &mut Matrix<f32, R, C>) {
-        let bias = Matrix::with_val(1.0);
-        *layer += &bias;
+    /// Returns the Euclidean distance between the weights of the two networks.
+    pub fn distance(&self, other: &Self) -> f32 {
+        self.weights()
+            .zip(other.weights())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt()
     }
 
-    fn activate<const R: usize, const C: usize>(layer: &mut Matrix<f32, R, C>) {
-        layer.apply(math::sigmoid);
+    /// Returns statistics of the weights of every layer, from the first hidden layer to the output
+    /// layer. The weights of a layer are the connections into it and its biases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::neuralnet::NeuralNetwork;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let nnet: NeuralNetwork<3, 4, 1, 2> = NeuralNetwork::new(&mut rng);
+    /// let stats = nnet.weight_stats();
+    ///
+    /// assert_eq!(stats.len(), 3);
+    /// assert!(stats.iter().all(|s| s.min >= -1.0 && s.max <= 1.0));
+    /// assert_eq!(nnet.distance(&nnet), 0.0);
+    /// ```
+    pub fn weight_stats(&self) -> Vec<WeightStats> {
+        let mut biases = self.hidden_biases.rows();
+        let mut stats = Vec::with_capacity(LAYERS + 1);
+
+        let first: Vec<f32> = self
+            .hidden_layer_in
+            .iter()
+            .chain(biases.next().into_iter().flatten())
+            .copied()
+            .collect();
+        stats.push(WeightStats::new(&first));
+
+        for (layer, bias) in self.hidden_layers.iter().zip(biases) {
+            let weights: Vec<f32> = layer.iter().chain(bias.iter()).copied().collect();
+            stats.push(WeightStats::new(&weights));
+        }
+
+        let last: Vec<f32> = self
+            .hidden_layer_out
+            .iter()
+            .chain(self.output_bias.iter())
+            .copied()
+            .collect();
+        stats.push(WeightStats::new(&last));
+
+        stats
+    }
+
+    fn weights(&self) -> impl Iterator<Item = &f32> {
+        self.hidden_layer_in
+            .iter()
+            .chain(self.hidden_layers.iter().flat_map(|layer| layer.iter()))
+            .chain(self.hidden_layer_out.iter())
+            .chain(self.hidden_biases.iter())
+            .chain(self.output_bias.iter())
+    }
+}
+
+/// Statistics of the weights of one layer of a network.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WeightStats {
+    /// Smallest weight.
+    pub min: f32,
+
+    /// Largest weight.
+    pub max: f32,
+
+    /// Mean of the weights.
+    pub mean: f32,
+
+    /// Standard deviation of the weights.
+    pub std: f32,
+}
+
+impl WeightStats {
+    fn new(weights: &[f32]) -> Self {
+        let count = weights.len() as f32;
+        let min = weights.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = weights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mean = weights.iter().sum::<f32>() / count;
+        let variance = weights.iter().map(|w| (w - mean) * (w - mean)).sum::<f32>() / count;
+
+        Self {
+            min,
+            max,
+            mean,
+            std: variance.sqrt(),
+        }
+    }
+}
+
+/// Trains a [`NeuralNetwork`] with stochastic gradient descent, e.g. to imitate recorded human
+/// play.
+///
+/// [`NeuralNetwork`]: struct.NeuralNetwork.html
+///
+/// # Examples
+///
+/// ```
+/// use dinai::math::Matrix;
+/// use dinai::neuralnet::{NeuralNetwork, SgdTrainer};
+/// use rand::SeedableRng;
+///
+/// let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+/// let mut nnet: NeuralNetwork<2, 4, 1> = NeuralNetwork::new(&mut rng);
+/// let samples = [
+///     (Matrix::from([[1.0, 0.0]]), Matrix::from([[0.9]])),
+///     (Matrix::from([[0.0, 1.0]]), Matrix::from([[0.1]])),
+/// ];
+///
+/// let trainer = SgdTrainer {
+///     learning_rate: 0.5,
+///     epochs: 500,
+/// };
+/// let loss = trainer.train(&mut nnet, &samples, &mut rng);
+///
+/// assert!(loss < 0.01);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SgdTrainer {
+    /// Factor of the gradient subtracted from the weights.
+    pub learning_rate: f32,
+
+    /// Number of passes over all samples.
+    pub epochs: usize,
+}
+
+impl Default for SgdTrainer {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            epochs: 100,
+        }
+    }
+}
+
+impl SgdTrainer {
+    /// Trains `nnet` on samples of inputs and their expected outputs, visited in a random order
+    /// every epoch. Returns the mean squared error of the last epoch.
+    pub fn train<
+        R,
+        const INPUTS: usize,
+        const HIDDEN: usize,
+        const OUTPUTS: usize,
+        const LAYERS: usize,
+    >(
+        &self,
+        nnet: &mut NeuralNetwork<INPUTS, HIDDEN, OUTPUTS, LAYERS>,
+        samples: &[(Matrix<f32, 1, INPUTS>, Matrix<f32, 1, OUTPUTS>)],
+        rng: &mut R,
+    ) -> f32
+    where
+        R: Rng + ?Sized,
+    {
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        let mut loss = 0.0;
+
+        for _ in 0..self.epochs {
+            order.shuffle(rng);
+
+            loss = 0.0;
+            for &i in order.iter() {
+                let (input, target) = &samples[i];
+                loss += nnet.backward(input, target, self.learning_rate);
+            }
+            loss /= samples.len().max(1) as f32;
+        }
+
+        loss
+    }
+}
+
+/// Neural network with a single recurrent hidden layer, whose state persists between calls to
+/// [`feed`] until it is [`reset`]. This lets the network react to things it saw in previous
+/// frames only.
+///
+/// The hidden layer uses the hyperbolic tangent and the outputs the sigmoid. The state is not
+/// serialized, a deserialized network starts with a reset state.
+///
+/// [`feed`]: #method.feed
+/// [`reset`]: #method.reset
+///
+/// # Examples
+///
+/// ```
+/// use dinai::math::Matrix;
+/// use dinai::neuralnet::RecurrentNetwork;
+///
+/// let mut nnet: RecurrentNetwork<2, 4, 1> = RecurrentNetwork::new(&mut rand::thread_rng());
+/// let input = Matrix::from([[0.5, -0.5]]);
+///
+/// let first = nnet.feed(&input);
+/// nnet.feed(&input);
+/// nnet.reset();
+///
+/// assert_eq!(nnet.feed(&input).as_ref(), first.as_ref());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurrentNetwork<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize> {
+    hidden_layer_in: Matrix<f32, INPUTS, HIDDEN>,
+
+    // Connections from the previous state of the hidden layer.
+    hidden_layer_rec: Matrix<f32, HIDDEN, HIDDEN>,
+
+    hidden_layer_out: Matrix<f32, HIDDEN, OUTPUTS>,
+
+    #[serde(default = "ones")]
+    hidden_bias: Matrix<f32, 1, HIDDEN>,
+    #[serde(default = "ones")]
+    output_bias: Matrix<f32, 1, OUTPUTS>,
+
+    #[serde(skip, default = "Matrix::new")]
+    state: Matrix<f32, 1, HIDDEN>,
+}
+
+impl<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize> Default
+    for RecurrentNetwork<INPUTS, HIDDEN, OUTPUTS>
+{
+    fn default() -> Self {
+        Self {
+            hidden_layer_in: Matrix::new(),
+            hidden_layer_rec: Matrix::new(),
+            hidden_layer_out: Matrix::new(),
+            hidden_bias: Matrix::new(),
+            output_bias: Matrix::new(),
+            state: Matrix::new(),
+        }
+    }
+}
+
+impl<const INPUTS: usize, const HIDDEN: usize, const OUTPUTS: usize>
+    RecurrentNetwork<INPUTS, HIDDEN, OUTPUTS>
+{
+    /// Creates new `RecurrentNetwork` with weights drawn from `rng` and a reset state.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            hidden_layer_in: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_layer_rec: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_layer_out: Matrix::with_random(-1.0, 1.0, rng),
+            hidden_bias: Matrix::with_random(-1.0, 1.0, rng),
+            output_bias: Matrix::with_random(-1.0, 1.0, rng),
+            state: Matrix::new(),
+        }
+    }
+
+    /// Feeds the neural network with the input, producing the outputs and updating the state of
+    /// the hidden layer.
+    pub fn feed(&mut self, input: &Matrix<f32, 1, INPUTS>) -> Matrix<f32, 1, OUTPUTS> {
+        let mut a = input.mul_matrix_f32(&self.hidden_layer_in);
+        a += &self.state.mul_matrix_f32(&self.hidden_layer_rec);
+        a += &self.hidden_bias;
+        Activation::Tanh.apply(&mut a);
+
+        let mut res = a.mul_matrix_f32(&self.hidden_layer_out);
+        res += &self.output_bias;
+        Activation::Sigmoid.apply(&mut res);
+
+        self.state = a;
+
+        res
+    }
+
+    /// Forgets everything the network has seen, e.g. when its player dies.
+    pub fn reset(&mut self) {
+        self.state = Matrix::new();
+    }
+
+    /// Crossovers two neural networks in order to produce a new child with a reset state.
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self {
+        self.crossover_with(other, CrossoverStrategy::Block, rng)
+    }
+
+    /// Crossovers two neural networks combining every weight matrix as described by `strategy`.
+    /// The child has a reset state.
+    pub fn crossover_with<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        strategy: CrossoverStrategy,
+        rng: &mut R,
+    ) -> Self {
+        Self {
+            hidden_layer_in: self.hidden_layer_in.crossover_with(
+                &other.hidden_layer_in,
+                strategy,
+                rng,
+            ),
+            hidden_layer_rec: self.hidden_layer_rec.crossover_with(
+                &other.hidden_layer_rec,
+                strategy,
+                rng,
+            ),
+            hidden_layer_out: self.hidden_layer_out.crossover_with(
+                &other.hidden_layer_out,
+                strategy,
+                rng,
+            ),
+            hidden_bias: self
+                .hidden_bias
+                .crossover_with(&other.hidden_bias, strategy, rng),
+            output_bias: self
+                .output_bias
+                .crossover_with(&other.output_bias, strategy, rng),
+            state: Matrix::new(),
+        }
+    }
+
+    /// Randomly mutates weights as described by `config`.
+    pub fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R) {
+        math::mutate_matrixf(&mut self.hidden_layer_in, config, rng);
+        math::mutate_matrixf(&mut self.hidden_layer_rec, config, rng);
+        math::mutate_matrixf(&mut self.hidden_layer_out, config, rng);
+        math::mutate_matrixf(&mut self.hidden_bias, config, rng);
+        math::mutate_matrixf(&mut self.output_bias, config, rng);
+    }
+
+    /// Returns the Euclidean distance between the weights of the two networks.
+    pub fn distance(&self, other: &Self) -> f32 {
+        self.weights()
+            .zip(other.weights())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    fn weights(&self) -> impl Iterator<Item = &f32> {
+        self.hidden_layer_in
+            .iter()
+            .chain(self.hidden_layer_rec.iter())
+            .chain(self.hidden_layer_out.iter())
+            .chain(self.hidden_bias.iter())
+            .chain(self.output_bias.iter())
+    }
+}
+
+// Adds `bias` to every row of `layer`.
+fn add_bias<const R: usize, const C: usize>(layer: &mut Matrix<f32, R, C>, bias: &[f32; C]) {
+    for row in 0..R {
+        for (col, b) in bias.iter().enumerate() {
+            layer[(row, col)] += b;
+        }
+    }
+}
+
+// Bias of networks saved before biases were learned.
+fn ones<const R: usize, const C: usize>() -> Matrix<f32, R, C> {
+    Matrix::with_val(1.0)
+}
+
+// Activation functions of networks saved before they were stored.
+fn sigmoids<const LAYERS: usize>() -> [Activation; LAYERS] {
+    [Activation::Sigmoid; LAYERS]
+}
+
+fn serialize_activations<S, const LAYERS: usize>(
+    activations: &[Activation; LAYERS],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(activations)
+}
+
+// Reads either one activation function per hidden layer or a single one used by all of them.
+fn hidden_activations<'de, D, const LAYERS: usize>(
+    deserializer: D,
+) -> Result<[Activation; LAYERS], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Activations {
+        All(Activation),
+        PerLayer(Vec<Activation>),
+    }
+
+    match Activations::deserialize(deserializer)? {
+        Activations::All(activation) => Ok([activation; LAYERS]),
+        Activations::PerLayer(activations) => {
+            let len = activations.len();
+            <[Activation; LAYERS]>::try_from(activations).map_err(|_| {
+                de::Error::custom(format!(
+                    "expected {} hidden activation functions, found {}",
+                    LAYERS, len
+                ))
+            })
+        }
+    }
+}
+
+// Reads the connections between the hidden layers of a network with `LAYERS` of them, which
+// would otherwise panic when fed.
+fn hidden_layers<'de, D, const HIDDEN: usize, const LAYERS: usize>(
+    deserializer: D,
+) -> Result<Vec<Matrix<f32, HIDDEN, HIDDEN>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let layers = Vec::<Matrix<f32, HIDDEN, HIDDEN>>::deserialize(deserializer)?;
+    if layers.len() != LAYERS.saturating_sub(1) {
+        return Err(de::Error::custom(format!(
+            "expected {} connections between hidden layers, found {}",
+            LAYERS.saturating_sub(1),
+            layers.len()
+        )));
+    }
+
+    Ok(layers)
+}
+
+/// Deserializes the layers of a [`DynNeuralNetwork`], rejecting an empty network and layers
+/// whose sizes do not chain, which would otherwise panic when fed.
+///
+/// [`DynNeuralNetwork`]: struct.DynNeuralNetwork.html
+fn dyn_layers<'de, D>(deserializer: D) -> Result<Vec<DynMatrix<f32>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let layers = Vec::<DynMatrix<f32>>::deserialize(deserializer)?;
+    if layers.is_empty() {
+        return Err(de::Error::custom("expected at least one layer"));
+    }
+    if layers
+        .iter()
+        .any(|layer| layer.rows() == 0 || layer.cols() == 0)
+    {
+        return Err(de::Error::custom("layer sizes must be positive"));
+    }
+    if let Some(i) = layers.windows(2).position(|w| w[0].cols() != w[1].rows()) {
+        return Err(de::Error::custom(format!(
+            "layer {} has {} outputs but layer {} has {} inputs",
+            i,
+            layers[i].cols(),
+            i + 1,
+            layers[i + 1].rows()
+        )));
+    }
+
+    Ok(layers)
+}
+
+/// Scales raw network inputs into `[-1, 1]`, so that large values such as positions in pixels do
+/// not saturate the neurons.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::math::Matrix;
+/// use dinai::neuralnet::InputNormalizer;
+///
+/// let normalizer = InputNormalizer::new([(0.0, 600.0), (0.0, 1280.0)]).unwrap();
+/// let input = normalizer.normalize(&Matrix::from([[300.0, 1600.0]]));
+///
+/// assert_eq!(input.as_ref()[0], [0.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputNormalizer<const INPUTS: usize> {
+    ranges: [(f32, f32); INPUTS],
+}
+
+impl<const INPUTS: usize> InputNormalizer<INPUTS> {
+    /// Creates a normalizer mapping every `(min, max)` range to `[-1, 1]`. Values outside the
+    /// range are clamped. Fails if `min` is not less than `max` for any input.
+    pub fn new(ranges: [(f32, f32); INPUTS]) -> Result<Self, Error> {
+        if let Some((i, (min, max))) = ranges
+            .iter()
+            .enumerate()
+            .find(|(_, (min, max))| min.partial_cmp(max) != Some(Ordering::Less))
+        {
+            return Err(Error::Config(format!(
+                "range {}..{} of input {} is empty",
+                min, max, i
+            )));
+        }
+
+        Ok(Self { ranges })
+    }
+
+    /// Returns the normalized `input`.
+    pub fn normalize(&self, input: &Matrix<f32, 1, INPUTS>) -> Matrix<f32, 1, INPUTS> {
+        let mut res = input.clone();
+        for (value, (min, max)) in res.iter_mut().zip(self.ranges.iter()) {
+            *value = (2.0 * (*value - min) / (max - min) - 1.0).clamp(-1.0, 1.0);
+        }
+
+        res
+    }
+}
+
+/// Neural network with the topology chosen at runtime.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::neuralnet::DynNeuralNetwork;
+///
+/// let nnet = DynNeuralNetwork::new(&[3, 8, 4, 1], &mut rand::thread_rng());
+/// let output = nnet.feed(&[0.5, 0.1, 0.0]);
+///
+/// assert_eq!(output.len(), 1);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynNeuralNetwork {
+    #[serde(deserialize_with = "dyn_layers")]
+    layers: Vec<DynMatrix<f32>>,
+}
+
+impl DynNeuralNetwork {
+    /// Creates new `DynNeuralNetwork` with given layer sizes and weights drawn from `rng`. The
+    /// first size is the number of inputs, the last one is the number of outputs and the ones in
+    /// between are hidden layers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if less than two sizes are given or any of them is zero.
+    pub fn new<R: Rng + ?Sized>(sizes: &[usize], rng: &mut R) -> Self {
+        assert!(
+            sizes.len() >= 2,
+            "at least input and output sizes are required"
+        );
+        assert!(
+            sizes.iter().all(|&size| size > 0),
+            "layer sizes must be positive"
+        );
+
+        let layers = sizes
+            .windows(2)
+            .map(|w| DynMatrix::with_random(w[0], w[1], -1.0, 1.0, rng))
+            .collect();
+
+        Self { layers }
+    }
+
+    /// Returns the layer sizes this network was created with.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![self.layers[0].rows()];
+        sizes.extend(self.layers.iter().map(|layer| layer.cols()));
+        sizes
+    }
+
+    /// Feeds the neural network with the input, producing as many values as there are outputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `input` differs from the number of inputs.
+    pub fn feed(&self, input: &[f32]) -> Vec<f32> {
+        let mut a = DynMatrix::from_row(input);
+
+        for layer in self.layers.iter() {
+            a = a.mul_matrix(layer);
+            a.add_matrix(&DynMatrix::with_val(a.rows(), a.cols(), 1.0));
+            a.apply(math::sigmoid);
+        }
+
+        a.as_slice().to_vec()
+    }
+
+    /// Crossovers two neural networks in order to produce a new child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the networks do not have the same topology.
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> Self {
+        assert_eq!(self.sizes(), other.sizes(), "topologies differ");
+
+        let layers = self
+            .layers
+            .iter()
+            .zip(other.layers.iter())
+            .map(|(a, b)| a.crossover(b, rng))
+            .collect();
+
+        Self { layers }
+    }
+
+    /// Randomly mutates weights as described by `config`.
+    pub fn mutate<R: Rng + ?Sized>(&mut self, config: &MutationConfig, rng: &mut R) {
+        for layer in self.layers.iter_mut() {
+            math::mutate_dyn_matrixf(layer, config, rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_activations() {
+        let mut nnet: NeuralNetwork<2, 3, 1, 2> = NeuralNetwork::new(&mut rand::thread_rng());
+        nnet.set_layer_activation(1, Activation::Relu);
+
+        let json = serde_json::to_string(&nnet).unwrap();
+        let loaded: NeuralNetwork<2, 3, 1, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.hidden_activations(),
+            [Activation::Sigmoid, Activation::Relu]
+        );
+
+        // A single activation function is used by all hidden layers.
+        let json = json.replace(
+            r#""hidden_activations":["Sigmoid","Relu"]"#,
+            r#""hidden_activation":"Tanh""#,
+        );
+        let loaded: NeuralNetwork<2, 3, 1, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.hidden_activations(), [Activation::Tanh; 2]);
+
+        let json = json.replace(
+            r#""hidden_activation":"Tanh""#,
+            r#""hidden_activations":["Tanh"]"#,
+        );
+        assert!(serde_json::from_str::<NeuralNetwork<2, 3, 1, 2>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_dyn_neural_network_serde() {
+        let nnet = DynNeuralNetwork::new(&[3, 8, 4, 1], &mut rand::thread_rng());
+
+        let json = serde_json::to_string(&nnet).unwrap();
+        let loaded: DynNeuralNetwork = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.sizes(), vec![3, 8, 4, 1]);
+        assert!(serde_json::from_str::<DynNeuralNetwork>(r#"{"layers":[]}"#).is_err());
+        assert!(serde_json::from_str::<DynNeuralNetwork>(
+            r#"{"layers":[
+                {"rows":1,"cols":2,"data":[0.0,0.0]},
+                {"rows":3,"cols":1,"data":[0.0,0.0,0.0]}
+            ]}"#
+        )
+        .is_err());
     }
 }
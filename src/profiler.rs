@@ -0,0 +1,239 @@
+//! Measuring how long the phases of a frame take.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::mem;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Part of a frame measured by the [`Profiler`].
+///
+/// [`Profiler`]: struct.Profiler.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    /// All fixed updates of the frame, including thinking, physics and breeding.
+    Update,
+
+    /// Evaluating the networks of the players.
+    Think,
+
+    /// Moving the players and the world.
+    Physics,
+
+    /// Drawing the frame.
+    Draw,
+
+    /// Breeding the next generation.
+    Breeding,
+}
+
+impl Phase {
+    /// Number of phases.
+    pub const COUNT: usize = 5;
+
+    /// All phases in the order of their columns in the CSV output.
+    pub const ALL: [Phase; Self::COUNT] = [
+        Phase::Update,
+        Phase::Think,
+        Phase::Physics,
+        Phase::Draw,
+        Phase::Breeding,
+    ];
+
+    /// Returns the lowercase name of this phase.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Update => "update",
+            Phase::Think => "think",
+            Phase::Physics => "physics",
+            Phase::Draw => "draw",
+            Phase::Breeding => "breeding",
+        }
+    }
+}
+
+/// Time spent in every phase of one frame.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FrameTimes {
+    durations: [Duration; Phase::COUNT],
+}
+
+impl FrameTimes {
+    /// Returns the time spent in `phase`.
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.durations[phase as usize]
+    }
+}
+
+/// Sums the time spent in every phase of the current frame and keeps a smoothed average over
+/// past frames.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::profiler::{Phase, Profiler};
+/// use std::time::Duration;
+///
+/// let mut profiler = Profiler::new();
+/// let sum = profiler.time(Phase::Think, || 1 + 1);
+/// profiler.add(Phase::Draw, Duration::from_millis(4));
+///
+/// let frame = profiler.end_frame();
+/// assert_eq!(sum, 2);
+/// assert_eq!(frame.get(Phase::Draw), Duration::from_millis(4));
+/// assert_eq!(profiler.average_ms(Phase::Draw), 4.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    current: FrameTimes,
+    average_ms: [f32; Phase::COUNT],
+    frames: u64,
+}
+
+impl Profiler {
+    const SMOOTHING: f32 = 0.9;
+
+    /// Creates a profiler with no measured frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` and adds the time it took to `phase`.
+    pub fn time<T, F: FnOnce() -> T>(&mut self, phase: Phase, f: F) -> T {
+        let start = Instant::now();
+        let res = f();
+        self.add(phase, start.elapsed());
+
+        res
+    }
+
+    /// Adds `elapsed` to the time spent in `phase` during the current frame.
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        self.current.durations[phase as usize] += elapsed;
+    }
+
+    /// Finishes the current frame, includes it in the averages and returns its times.
+    pub fn end_frame(&mut self) -> FrameTimes {
+        let frame = mem::take(&mut self.current);
+
+        for (average, duration) in self.average_ms.iter_mut().zip(frame.durations.iter()) {
+            let sample = duration.as_nanos() as f32 / 1e6;
+            *average = if self.frames == 0 {
+                sample
+            } else {
+                *average * Self::SMOOTHING + sample * (1.0 - Self::SMOOTHING)
+            };
+        }
+        self.frames += 1;
+
+        frame
+    }
+
+    /// Returns the smoothed time in milliseconds spent in `phase` per frame.
+    pub fn average_ms(&self, phase: Phase) -> f32 {
+        self.average_ms[phase as usize]
+    }
+
+    /// Returns the number of finished frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+/// Writes the times of every frame as CSV, one column per [`Phase`] in milliseconds.
+///
+/// [`Phase`]: enum.Phase.html
+#[derive(Debug)]
+pub struct ProfileRecorder<W: Write> {
+    writer: W,
+    frame: u64,
+}
+
+impl ProfileRecorder<BufWriter<File>> {
+    /// Creates a recorder writing into a new file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> ProfileRecorder<W> {
+    /// Creates a recorder writing into `writer` and writes the CSV header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        write!(writer, "frame")?;
+        for phase in Phase::ALL.iter() {
+            write!(writer, ",{}_ms", phase.name())?;
+        }
+        writeln!(writer)?;
+
+        Ok(Self { writer, frame: 0 })
+    }
+
+    /// Writes one row with the times of the next frame. Rows are not flushed, since one is
+    /// written every frame, so [`flush`] has to be called once recording is done.
+    ///
+    /// [`flush`]: #method.flush
+    pub fn record(&mut self, frame: &FrameTimes) -> io::Result<()> {
+        write!(self.writer, "{}", self.frame)?;
+        for phase in Phase::ALL.iter() {
+            write!(
+                self.writer,
+                ",{}",
+                frame.get(*phase).as_nanos() as f64 / 1e6
+            )?;
+        }
+        writeln!(self.writer)?;
+        self.frame += 1;
+
+        Ok(())
+    }
+
+    /// Writes all buffered rows.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average() {
+        let mut profiler = Profiler::new();
+
+        profiler.add(Phase::Update, Duration::from_millis(10));
+        profiler.add(Phase::Update, Duration::from_millis(10));
+        assert_eq!(
+            profiler.end_frame().get(Phase::Update),
+            Duration::from_millis(20)
+        );
+        assert_eq!(profiler.average_ms(Phase::Update), 20.0);
+
+        profiler.end_frame();
+        assert!((profiler.average_ms(Phase::Update) - 18.0).abs() < 1e-4);
+        assert_eq!(profiler.frames(), 2);
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = ProfileRecorder::new(Vec::new()).unwrap();
+        let mut profiler = Profiler::new();
+
+        profiler.add(Phase::Draw, Duration::from_micros(1500));
+        recorder.record(&profiler.end_frame()).unwrap();
+
+        let csv = String::from_utf8(recorder.into_inner()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("frame,update_ms,think_ms,physics_ms,draw_ms,breeding_ms")
+        );
+        assert_eq!(lines.next(), Some("0,0,0,0,1.5,0"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! Recording of the rendered frames into a video or an animated GIF.
+//!
+//! Frames are piped into an `ffmpeg` process, which has to be installed, and the output format is
+//! chosen by `ffmpeg` from the file extension, e.g. `.mp4` or `.gif`.
+//!
+//! This module is only available with the `recorder` feature.
+
+use crate::Error;
+use log::info;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Captures every `interval`-th frame of a canvas and encodes them with `ffmpeg`.
+///
+/// All frames have the size given when the recording starts. If the window grows later only its
+/// upper left part is recorded, if it shrinks the rest of the frame stays black.
+///
+/// Dropping a recorder without calling [`finish`] still closes the video, e.g. when the game loop
+/// stops with an error.
+///
+/// [`finish`]: #method.finish
+pub struct Recorder {
+    ffmpeg: Child,
+    stdin: Option<ChildStdin>,
+    width: u32,
+    height: u32,
+    interval: u32,
+    frame: u32,
+}
+
+impl Recorder {
+    /// Starts an `ffmpeg` process writing to `path`. Frames are captured `width` x `height`
+    /// pixels large and every `interval`-th frame is played at `fps` frames per second.
+    pub fn start<P: AsRef<Path>>(
+        path: P,
+        width: u32,
+        height: u32,
+        fps: u32,
+        interval: u32,
+    ) -> Result<Self, Error> {
+        let mut ffmpeg = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error"])
+            .args(["-f", "rawvideo", "-pixel_format", "rgb24"])
+            .arg("-video_size")
+            .arg(format!("{}x{}", width, height))
+            .arg("-framerate")
+            .arg(fps.to_string())
+            .args(["-i", "-"])
+            .arg(path.as_ref())
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        let stdin = ffmpeg.stdin.take();
+        info!(
+            "Recording {}x{} frames into {}",
+            width,
+            height,
+            path.as_ref().display()
+        );
+
+        Ok(Self {
+            ffmpeg,
+            stdin,
+            width,
+            height,
+            interval: interval.max(1),
+            frame: 0,
+        })
+    }
+
+    /// Captures the current content of `canvas` if this is the `interval`-th frame. Must be
+    /// called after drawing and before presenting the frame.
+    pub fn capture(&mut self, canvas: &Canvas<Window>) -> Result<(), Error> {
+        self.frame += 1;
+        if !self.frame.is_multiple_of(self.interval) {
+            return Ok(());
+        }
+
+        let (output_width, output_height) = canvas.output_size()?;
+        let width = self.width.min(output_width);
+        let height = self.height.min(output_height);
+        let pixels = if (width, height) == (self.width, self.height) {
+            canvas.read_pixels(Rect::new(0, 0, width, height), PixelFormatEnum::RGB24)?
+        } else {
+            // A shrunk window is padded with black to the size of the video.
+            let mut frame = vec![0; self.width as usize * self.height as usize * 3];
+            if width > 0 && height > 0 {
+                let pixels =
+                    canvas.read_pixels(Rect::new(0, 0, width, height), PixelFormatEnum::RGB24)?;
+                let row_len = width as usize * 3;
+                for (y, row) in pixels.chunks_exact(row_len).enumerate() {
+                    let start = y * self.width as usize * 3;
+                    frame[start..start + row_len].copy_from_slice(row);
+                }
+            }
+            frame
+        };
+
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "recording finished"))?;
+        stdin.write_all(&pixels)?;
+
+        Ok(())
+    }
+
+    /// Stops the recording and waits until `ffmpeg` writes the output file.
+    pub fn finish(mut self) -> Result<(), Error> {
+        // Closing the pipe tells ffmpeg that there are no more frames.
+        self.stdin.take();
+
+        let status = self.ffmpeg.wait()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("ffmpeg {}", status)).into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // Unless the recording was finished, close the pipe and let ffmpeg write what it got.
+        if self.stdin.take().is_some() {
+            let _ = self.ffmpeg.wait();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Recording a generation so that it can be watched again.
+
+use crate::config::Config;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// An obstacle of kind `K` spawned in the given step of a generation.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Spawn<K> {
+    /// Step of the generation in which the obstacle spawned.
+    pub step: u32,
+
+    /// Kind of the spawned obstacle.
+    pub kind: K,
+}
+
+/// Everything needed to repeat the run of one player through a generation: the parameters of the
+/// world, the obstacles spawned in it and the actions of type `A` the player took in every step.
+///
+/// # Examples
+///
+/// ```no_run
+/// use dinai::config::Config;
+/// use dinai::replay::{Replay, Spawn};
+///
+/// let replay = Replay {
+///     seed: 42,
+///     generation: 412,
+///     score: 31.5,
+///     config: Config::default(),
+///     spawns: vec![Spawn { step: 0, kind: 'c' }],
+///     actions: vec![0u8, 1, 1, 0],
+/// };
+///
+/// let path = replay.save("replays").unwrap();
+/// let restored: Replay<char, u8> = Replay::load(path).unwrap();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay<K, A> {
+    /// Seed of the recorded run.
+    pub seed: u64,
+
+    /// Recorded generation.
+    pub generation: u32,
+
+    /// Score the player reached.
+    pub score: f32,
+
+    /// Parameters of the world during the generation.
+    pub config: Config,
+
+    /// Obstacles in the order they spawned.
+    pub spawns: Vec<Spawn<K>>,
+
+    /// Action of the player in every step until it died.
+    pub actions: Vec<A>,
+}
+
+impl<K: Serialize, A: Serialize> Replay<K, A> {
+    /// Writes this replay as JSON into the directory `dir`, which is created if needed. The file
+    /// is named after the generation and its path is returned.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> io::Result<PathBuf> {
+        fs::create_dir_all(&dir)?;
+
+        let path = dir
+            .as_ref()
+            .join(format!("generation-{:05}.json", self.generation));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+
+        Ok(path)
+    }
+}
+
+impl<K: DeserializeOwned, A: DeserializeOwned> Replay<K, A> {
+    /// Reads a replay previously written by [`save`].
+    ///
+    /// [`save`]: #method.save
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
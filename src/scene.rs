@@ -0,0 +1,224 @@
+//! Game modes as a stack of scenes.
+//!
+//! Only the scene on top of a [`SceneStack`] handles input and is updated. It switches to another
+//! mode by returning a [`Transition`], e.g. a menu pushes the training and the training pops
+//! itself to get back to the menu.
+//!
+//! [`SceneStack`]: struct.SceneStack.html
+//! [`Transition`]: enum.Transition.html
+
+use crate::game::{Context, Game};
+use crate::Error;
+
+/// What the [`SceneStack`] does after a scene handled input or was updated.
+///
+/// [`SceneStack`]: struct.SceneStack.html
+pub enum Transition {
+    /// The scene stays on top.
+    Stay,
+
+    /// Puts a new scene on top of the current one, which resumes once the new one is popped.
+    Push(Box<dyn Scene>),
+
+    /// Removes the current scene.
+    Pop,
+
+    /// Replaces the current scene.
+    Replace(Box<dyn Scene>),
+
+    /// Removes all scenes, which closes the window.
+    Quit,
+}
+
+/// One mode of the game, e.g. a menu or the training.
+pub trait Scene {
+    /// Draws the current state, `interpolation` seconds of simulated time after the last update.
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error>;
+
+    /// Handles the input polled at the start of the frame.
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<Transition, Error>;
+
+    /// Advances the scene by one fixed update of `ctx.step_s` seconds.
+    fn update(&mut self, ctx: &mut Context) -> Result<Transition, Error>;
+
+    /// Called after the window has been resized. Scenes below the top one are told as well.
+    fn on_resize(&mut self, _ctx: &mut Context) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Returns the number of finished rounds and the best score reached so far, which decide when
+    /// fast-forwarding stops.
+    fn progress(&self) -> (u32, f32) {
+        (0, 0.0)
+    }
+
+    /// Whether the scene below is drawn first, so that this one is shown over it.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// Scenes of which the top one runs. The window is closed once the stack is empty.
+///
+/// A `SceneStack` is a [`Game`], so it is run by a [`GameLoop`]:
+///
+/// ```no_run
+/// # use dinai::config::Config;
+/// # use dinai::game::{Context, GameLoop};
+/// # use dinai::scene::{Scene, SceneStack, Transition};
+/// # use dinai::Error;
+/// # struct Menu;
+/// # impl Scene for Menu {
+/// #     fn draw(&mut self, _: &mut Context, _: f32) -> Result<(), Error> { Ok(()) }
+/// #     fn handle_input(&mut self, _: &mut Context) -> Result<Transition, Error> {
+/// #         Ok(Transition::Stay)
+/// #     }
+/// #     fn update(&mut self, _: &mut Context) -> Result<Transition, Error> {
+/// #         Ok(Transition::Stay)
+/// #     }
+/// # }
+/// let mut scenes = SceneStack::new(Box::new(Menu));
+/// GameLoop::new("menu", &Config::default()).run(&mut scenes).unwrap();
+/// ```
+///
+/// [`Game`]: ../game/trait.Game.html
+/// [`GameLoop`]: ../game/struct.GameLoop.html
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    /// Creates a stack with the single scene `scene`.
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self {
+            scenes: vec![scene],
+        }
+    }
+
+    /// Returns the number of scenes.
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns true if there are no scenes left.
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Applies `transition` returned by the top scene.
+    pub fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Stay => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.scenes.clear(),
+        }
+    }
+
+    // Applies `transition` and closes the window once no scene is left.
+    fn apply_in(&mut self, ctx: &mut Context, transition: Transition) {
+        self.apply(transition);
+        if self.scenes.is_empty() {
+            ctx.game_window.close();
+        }
+    }
+}
+
+impl Game for SceneStack {
+    fn draw(&mut self, ctx: &mut Context, interpolation: f32) -> Result<(), Error> {
+        // Overlays are drawn over the scenes below them, down to the first one that is not.
+        let bottom = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+
+        for scene in self.scenes.iter_mut().skip(bottom) {
+            scene.draw(ctx, interpolation)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.handle_input(ctx)?;
+            self.apply_in(ctx, transition);
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.update(ctx)?;
+            self.apply_in(ctx, transition);
+        }
+
+        Ok(())
+    }
+
+    fn on_resize(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        for scene in self.scenes.iter_mut() {
+            scene.on_resize(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn progress(&self) -> (u32, f32) {
+        self.scenes
+            .last()
+            .map_or((0, 0.0), |scene| scene.progress())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Named(&'static str);
+
+    impl Scene for Named {
+        fn draw(&mut self, _ctx: &mut Context, _interpolation: f32) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn handle_input(&mut self, _ctx: &mut Context) -> Result<Transition, Error> {
+            Ok(Transition::Stay)
+        }
+
+        fn update(&mut self, _ctx: &mut Context) -> Result<Transition, Error> {
+            Ok(Transition::Stay)
+        }
+
+        fn progress(&self) -> (u32, f32) {
+            (self.0.len() as u32, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut scenes = SceneStack::new(Box::new(Named("menu")));
+
+        scenes.apply(Transition::Push(Box::new(Named("training"))));
+        assert_eq!(scenes.len(), 2);
+        assert_eq!(scenes.progress(), (8, 0.0));
+
+        scenes.apply(Transition::Replace(Box::new(Named("replay"))));
+        assert_eq!(scenes.len(), 2);
+        assert_eq!(scenes.progress(), (6, 0.0));
+
+        scenes.apply(Transition::Pop);
+        assert_eq!(scenes.progress(), (4, 0.0));
+
+        scenes.apply(Transition::Quit);
+        assert!(scenes.is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+//! Per-generation training statistics.
+
+use crate::genetic::MutationConfig;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Fitness summary of one generation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationStats {
+    /// Number of the generation.
+    pub generation: u32,
+
+    /// Highest fitness in the generation.
+    pub best: f32,
+
+    /// Average fitness of the generation.
+    pub mean: f32,
+
+    /// Median fitness of the generation.
+    pub median: f32,
+}
+
+impl GenerationStats {
+    /// Summarizes the fitness values of a generation. Returns `None` if there are none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dinai::stats::GenerationStats;
+    ///
+    /// let stats = GenerationStats::from_fitness(3, &[1.0, 4.0, 2.0, 5.0]).unwrap();
+    ///
+    /// assert_eq!(stats.best, 5.0);
+    /// assert_eq!(stats.mean, 3.0);
+    /// assert_eq!(stats.median, 3.0);
+    /// ```
+    pub fn from_fitness(generation: u32, fitness: &[f32]) -> Option<Self> {
+        if fitness.is_empty() {
+            return None;
+        }
+
+        let mut sorted = fitness.to_vec();
+        sorted.sort_unstable_by(f32::total_cmp);
+
+        let len = sorted.len();
+        let median = if len.is_multiple_of(2) {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+
+        Some(Self {
+            generation,
+            best: sorted[len - 1],
+            mean: sorted.iter().sum::<f32>() / len as f32,
+            median,
+        })
+    }
+}
+
+/// The statistics of all finished generations.
+#[derive(Debug, Clone, Default)]
+pub struct StatsHistory {
+    generations: Vec<GenerationStats>,
+}
+
+impl StatsHistory {
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends the statistics of a finished generation.
+    pub fn record(&mut self, stats: GenerationStats) {
+        self.generations.push(stats);
+    }
+
+    /// Returns the statistics of all recorded generations, oldest first.
+    pub fn generations(&self) -> &[GenerationStats] {
+        &self.generations
+    }
+
+    /// Returns the statistics of at most `count` most recent generations, oldest first.
+    pub fn last(&self, count: usize) -> &[GenerationStats] {
+        let start = self.generations.len().saturating_sub(count);
+        &self.generations[start..]
+    }
+
+    /// Returns the highest best fitness of all recorded generations.
+    pub fn best(&self) -> Option<f32> {
+        self.generations.iter().map(|s| s.best).reduce(f32::max)
+    }
+}
+
+/// Writes the statistics of every generation as a CSV row, so that training runs can be analyzed
+/// afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use dinai::genetic::MutationConfig;
+/// use dinai::stats::{GenerationStats, StatsRecorder};
+///
+/// let mut recorder = StatsRecorder::new(Vec::new()).unwrap();
+/// let stats = GenerationStats::from_fitness(0, &[1.0, 2.0]).unwrap();
+/// recorder.record(&stats, 2.0, &MutationConfig::default()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct StatsRecorder<W: Write> {
+    writer: W,
+}
+
+impl StatsRecorder<BufWriter<File>> {
+    /// Creates a recorder writing into a new file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> StatsRecorder<W> {
+    /// Creates a recorder writing into `writer` and writes the CSV header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(
+            writer,
+            "generation,best,mean,median,duration_s,mutation_rate,mutation_sigma"
+        )?;
+
+        Ok(Self { writer })
+    }
+
+    /// Writes one row with the statistics of a finished generation, the simulated time in seconds
+    /// it lasted and the mutation parameters used to breed it. The row is flushed immediately.
+    pub fn record(
+        &mut self,
+        stats: &GenerationStats,
+        duration_s: f32,
+        mutation: &MutationConfig,
+    ) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{}",
+            stats.generation,
+            stats.best,
+            stats.mean,
+            stats.median,
+            duration_s,
+            mutation.rate,
+            mutation.sigma
+        )?;
+
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odd_median() {
+        let stats = GenerationStats::from_fitness(0, &[3.0, 1.0, 2.0]).unwrap();
+
+        assert_eq!(stats.median, 2.0);
+    }
+
+    #[test]
+    fn test_nan() {
+        let stats = GenerationStats::from_fitness(0, &[1.0, f32::NAN, 2.0]).unwrap();
+
+        assert_eq!(stats.median, 2.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(GenerationStats::from_fitness(0, &[]), None);
+    }
+
+    #[test]
+    fn test_history_last() {
+        let mut history = StatsHistory::new();
+        for (generation, best) in [1.0, 3.0, 2.0].iter().enumerate() {
+            history.record(GenerationStats {
+                generation: generation as u32,
+                best: *best,
+                mean: 0.0,
+                median: 0.0,
+            });
+        }
+
+        assert_eq!(history.last(2).len(), 2);
+        assert_eq!(history.last(2)[0].generation, 1);
+        assert_eq!(history.last(10).len(), 3);
+        assert_eq!(history.best(), Some(3.0));
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = StatsRecorder::new(Vec::new()).unwrap();
+        let stats = GenerationStats::from_fitness(7, &[1.0, 2.0, 6.0]).unwrap();
+
+        recorder
+            .record(&stats, 6.5, &MutationConfig::default())
+            .unwrap();
+
+        let csv = String::from_utf8(recorder.into_inner()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("generation,best,mean,median,duration_s,mutation_rate,mutation_sigma")
+        );
+        assert_eq!(lines.next(), Some("7,6,3,2,6.5,0.05,0.2"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -1,14 +1,48 @@
 //! A wrapper for SDL2 library.
 
-use sdl2::event::Event;
+use crate::math::{AABBf, Vector2f};
+use crate::Error;
+use log::{debug, info, warn};
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
+use sdl2::keyboard::TextInputUtil;
+use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
-use sdl2::rect::Rect;
-use sdl2::render::{Canvas, TextureCreator};
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Canvas, Texture, TextureCreator};
+use sdl2::rwops::RWops;
 use sdl2::ttf::{Font, Sdl2TtfContext};
 use sdl2::video::{Window, WindowContext};
-use sdl2::EventPump;
-use std::collections::HashSet;
+use sdl2::{EventPump, GameControllerSubsystem};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The font bundled into the binary, used when no other font is given.
+const DEFAULT_FONT: &[u8] = include_bytes!("../Inconsolata-Bold.ttf");
+
+/// Point size of the default font. Text is rendered large and scaled down when drawn.
+pub const DEFAULT_POINT_SIZE: u16 = 128;
+
+/// Where a [`TextRenderer`] loads its font from.
+///
+/// [`TextRenderer`]: struct.TextRenderer.html
+#[derive(Debug, Copy, Clone)]
+pub enum FontSource<'a> {
+    /// A TrueType font file.
+    Path(&'a Path),
+
+    /// TrueType font data in memory, e.g. embedded with `include_bytes!`.
+    Bytes(&'a [u8]),
+}
+
+impl Default for FontSource<'_> {
+    fn default() -> Self {
+        FontSource::Bytes(DEFAULT_FONT)
+    }
+}
 
 /// A config that specifies window constants.
 pub struct WindowConfig {
@@ -20,6 +54,262 @@ pub struct WindowConfig {
 
     /// Height of the window.
     pub height: u32,
+
+    /// Whether the user can resize the window.
+    pub resizable: bool,
+
+    /// Whether presenting a frame waits for the vertical sync of the display.
+    pub vsync: bool,
+}
+
+/// Measures how many times per second something happens, e.g. frames or updates. The rate is
+/// sampled twice a second and smoothed exponentially.
+#[derive(Debug, Clone)]
+pub struct RateCounter {
+    rate: f32,
+    count: u32,
+    window_start: Instant,
+}
+
+impl Default for RateCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateCounter {
+    const WINDOW_S: f32 = 0.5;
+    const SMOOTHING: f32 = 0.5;
+
+    /// Creates a counter with no ticks.
+    pub fn new() -> Self {
+        Self {
+            rate: 0.0,
+            count: 0,
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Records one occurrence.
+    pub fn tick(&mut self) {
+        self.count += 1;
+
+        let elapsed = self.window_start.elapsed().as_secs_f32();
+        if elapsed >= Self::WINDOW_S {
+            let sample = self.count as f32 / elapsed;
+            self.rate = if self.rate == 0.0 {
+                sample
+            } else {
+                self.rate * Self::SMOOTHING + sample * (1.0 - Self::SMOOTHING)
+            };
+
+            self.count = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    /// Returns the smoothed number of ticks per second.
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+}
+
+/// An input event translated from `SDL2`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// The user asked to close the window.
+    Quit,
+
+    /// The window has been resized to the given size.
+    Resized {
+        /// New width of the window.
+        width: u32,
+
+        /// New height of the window.
+        height: u32,
+    },
+
+    /// A key has been pressed down.
+    KeyDown {
+        /// The pressed key.
+        key: Keycode,
+
+        /// True if the event was generated by holding the key down.
+        repeat: bool,
+    },
+
+    /// A key has been released.
+    KeyUp {
+        /// The released key.
+        key: Keycode,
+    },
+
+    /// Text typed while text input is active.
+    TextInput(String),
+
+    /// A game controller button has been pressed down.
+    ButtonDown(Button),
+
+    /// A game controller button has been released.
+    ButtonUp(Button),
+
+    /// A game controller axis has moved to a position in `[-1, 1]`.
+    AxisMotion {
+        /// The moved axis.
+        axis: Axis,
+
+        /// New position of the axis.
+        value: f32,
+    },
+
+    /// The mouse has moved to the given position on the canvas.
+    MouseMotion {
+        /// x coordinate of the mouse.
+        x: i32,
+
+        /// y coordinate of the mouse.
+        y: i32,
+    },
+
+    /// A mouse button has been pressed down at the given position.
+    MouseDown {
+        /// The pressed button.
+        button: MouseButton,
+
+        /// x coordinate of the mouse.
+        x: i32,
+
+        /// y coordinate of the mouse.
+        y: i32,
+    },
+
+    /// A mouse button has been released at the given position.
+    MouseUp {
+        /// The released button.
+        button: MouseButton,
+
+        /// x coordinate of the mouse.
+        x: i32,
+
+        /// y coordinate of the mouse.
+        y: i32,
+    },
+
+    /// The mouse wheel has been scrolled, positive values away from the user.
+    MouseWheel {
+        /// Scrolled amount.
+        y: i32,
+    },
+}
+
+impl InputEvent {
+    /// Translates an `SDL2` event. Returns `None` for events without a translation.
+    pub fn from_sdl(event: Event) -> Option<Self> {
+        let event = match event {
+            Event::Quit { .. } => InputEvent::Quit,
+            Event::Window {
+                win_event: WindowEvent::SizeChanged(width, height),
+                ..
+            } => InputEvent::Resized {
+                width: width as u32,
+                height: height as u32,
+            },
+            Event::KeyDown {
+                keycode: Some(key),
+                repeat,
+                ..
+            } => InputEvent::KeyDown { key, repeat },
+            Event::KeyUp {
+                keycode: Some(key), ..
+            } => InputEvent::KeyUp { key },
+            Event::TextInput { text, .. } => InputEvent::TextInput(text),
+            Event::ControllerButtonDown { button, .. } => InputEvent::ButtonDown(button),
+            Event::ControllerButtonUp { button, .. } => InputEvent::ButtonUp(button),
+            Event::ControllerAxisMotion { axis, value, .. } => InputEvent::AxisMotion {
+                axis,
+                value: (value as f32 / i16::MAX as f32).max(-1.0),
+            },
+            Event::MouseMotion { x, y, .. } => InputEvent::MouseMotion { x, y },
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => InputEvent::MouseDown {
+                button: mouse_btn,
+                x,
+                y,
+            },
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => InputEvent::MouseUp {
+                button: mouse_btn,
+                x,
+                y,
+            },
+            Event::MouseWheel { y, .. } => InputEvent::MouseWheel { y },
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
+
+/// Maps world coordinates to the screen for the drawing helpers of [`GameWindow`].
+///
+/// [`GameWindow`]: struct.GameWindow.html
+///
+/// # Examples
+///
+/// ```
+/// use dinai::math::Vector2f;
+/// use dinai::window::Camera;
+///
+/// let mut camera = Camera::default();
+/// camera.zoom_at(Vector2f::from_coords(100.0, 100.0), 2.0);
+///
+/// let screen = camera.world_to_screen(Vector2f::from_coords(100.0, 100.0));
+///
+/// assert_eq!(screen, Vector2f::from_coords(100.0, 100.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    /// World position shown in the upper left corner of the screen.
+    pub offset: Vector2f,
+
+    /// Number of screen pixels per world unit.
+    pub zoom: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            offset: Vector2f::new(),
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    /// Converts a world position to a position on the screen.
+    pub fn world_to_screen(&self, point: Vector2f) -> Vector2f {
+        (point - self.offset) * self.zoom
+    }
+
+    /// Converts a position on the screen to a world position.
+    pub fn screen_to_world(&self, point: Vector2f) -> Vector2f {
+        point / self.zoom + self.offset
+    }
+
+    /// Multiplies the zoom by `factor` while keeping the world position under `screen_point` in
+    /// place.
+    pub fn zoom_at(&mut self, screen_point: Vector2f, factor: f32) {
+        let anchor = self.screen_to_world(screen_point);
+        self.zoom *= factor;
+        self.offset = anchor - screen_point / self.zoom;
+    }
+
+    /// Moves the camera so that `target` is shown at `screen_point`.
+    pub fn follow(&mut self, target: Vector2f, screen_point: Vector2f) {
+        self.offset = target - screen_point / self.zoom;
+    }
 }
 
 /// A custom window wrapper for the game.
@@ -33,16 +323,33 @@ pub struct WindowConfig {
 ///     title: "Title",
 ///     width: 1280,
 ///     height: 720,
+///     resizable: false,
+///     vsync: true,
 /// };
 ///
 /// let game_window = GameWindow::new(config).unwrap();
 /// ```
 pub struct GameWindow {
     config: WindowConfig,
-    canvas: Canvas<Window>,
+
+    // Only `None` while the canvas is being recreated.
+    canvas: Option<Canvas<Window>>,
     event_pump: EventPump,
+    controller_subsystem: GameControllerSubsystem,
+    controllers: Vec<GameController>,
+    pressed_buttons: HashSet<Button>,
+    text_input_util: TextInputUtil,
+    text_input: String,
     pressed_keys: HashSet<Keycode>,
+    just_pressed_keys: HashSet<Keycode>,
+    just_released_keys: HashSet<Keycode>,
     should_close: bool,
+    resized: bool,
+    events: Vec<InputEvent>,
+    camera: Camera,
+    frame_limit: Option<u32>,
+    last_present: Instant,
+    fps: RateCounter,
 }
 
 impl GameWindow {
@@ -51,66 +358,301 @@ impl GameWindow {
     /// order to render on the canvas or poll events.
     ///
     /// [`WindowConfig`]: struct.WindowConfig.html
-    pub fn new(config: WindowConfig) -> Result<Self, String> {
+    pub fn new(config: WindowConfig) -> Result<Self, Error> {
         let sdl_context = sdl2::init()?;
         let video_subsystem = sdl_context.video()?;
 
-        let window = video_subsystem
-            .window(config.title, config.width, config.height)
-            .position_centered()
-            .build()
-            .map_err(|e| e.to_string())?;
+        let mut window_builder = video_subsystem.window(config.title, config.width, config.height);
+        window_builder.position_centered();
+        if config.resizable {
+            window_builder.resizable();
+        }
+
+        let window = window_builder.build()?;
 
-        let canvas = window
-            .into_canvas()
-            .present_vsync()
-            .build()
-            .map_err(|e| e.to_string())?;
+        let canvas = build_canvas(window, config.vsync)?;
+        info!(
+            "SDL {} initialized: video driver {}, renderer {}, window {}x{}, vsync {}",
+            sdl2::version::version(),
+            video_subsystem.current_video_driver(),
+            canvas.info().name,
+            config.width,
+            config.height,
+            if config.vsync { "on" } else { "off" }
+        );
+
+        // SDL starts with text input enabled, it is only wanted while a string is being typed.
+        let text_input_util = video_subsystem.text_input();
+        text_input_util.stop();
 
         let event_pump = sdl_context.event_pump()?;
 
+        // Already connected controllers are reported as added by the first poll.
+        let controller_subsystem = sdl_context.game_controller()?;
+
         Ok(GameWindow {
             config,
-            canvas,
+            canvas: Some(canvas),
             event_pump,
+            controller_subsystem,
+            controllers: Vec::new(),
+            pressed_buttons: HashSet::new(),
+            text_input_util,
+            text_input: String::new(),
             pressed_keys: HashSet::new(),
+            just_pressed_keys: HashSet::new(),
+            just_released_keys: HashSet::new(),
             should_close: false,
+            resized: false,
+            events: Vec::new(),
+            camera: Camera::default(),
+            frame_limit: None,
+            last_present: Instant::now(),
+            fps: RateCounter::new(),
         })
     }
 
     /// Poll the `SDL2` events and handle them.
-    pub fn poll(&mut self) {
+    ///
+    /// Returns the events received since the previous poll, which are also available through
+    /// [`events`](#method.events) until the next poll.
+    pub fn poll(&mut self) -> &[InputEvent] {
         let events = self.event_pump.poll_iter().collect::<Vec<_>>();
 
+        self.resized = false;
+        self.just_pressed_keys.clear();
+        self.just_released_keys.clear();
+        self.events.clear();
+
         for event in events {
             match event {
-                Event::Quit { .. } => self.should_close = true,
-                Event::KeyDown {
-                    keycode: Some(key_code),
-                    ..
-                } => {
-                    self.pressed_keys.insert(key_code);
+                Event::ControllerDeviceAdded { which, .. } => {
+                    // A controller that cannot be opened is simply ignored.
+                    match self.controller_subsystem.open(which) {
+                        Ok(controller) => {
+                            info!("Controller {} connected", controller.name());
+                            self.controllers.push(controller);
+                        }
+                        Err(err) => warn!("Cannot open controller {}: {}", which, err),
+                    }
                 }
-                Event::KeyUp {
-                    keycode: Some(key_code),
-                    ..
-                } => {
-                    self.pressed_keys.remove(&key_code);
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    debug!("Controller {} disconnected", which);
+                    self.controllers.retain(|c| c.instance_id() != which);
+                    if self.controllers.is_empty() {
+                        self.pressed_buttons.clear();
+                    }
                 }
                 _ => {}
-            };
+            }
+
+            if let Some(event) = InputEvent::from_sdl(event) {
+                self.handle_event(&event);
+                self.events.push(event);
+            }
         }
+
+        &self.events
+    }
+
+    fn handle_event(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Quit => self.should_close = true,
+            InputEvent::Resized { width, height } => {
+                self.config.width = width;
+                self.config.height = height;
+                self.resized = true;
+            }
+            InputEvent::KeyDown { key, repeat } => {
+                self.pressed_keys.insert(key);
+                if !repeat {
+                    self.just_pressed_keys.insert(key);
+                }
+
+                if key == Keycode::Backspace && self.is_text_input_active() {
+                    self.text_input.pop();
+                }
+            }
+            InputEvent::KeyUp { key } => {
+                self.pressed_keys.remove(&key);
+                self.just_released_keys.insert(key);
+            }
+            InputEvent::TextInput(ref text) => {
+                self.text_input.push_str(text);
+            }
+            InputEvent::ButtonDown(button) => {
+                self.pressed_buttons.insert(button);
+            }
+            InputEvent::ButtonUp(button) => {
+                self.pressed_buttons.remove(&button);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the events received by the last [`poll`].
+    ///
+    /// [`poll`]: #method.poll
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
     }
 
     /// Clears the screen with the given color.
     pub fn clear(&mut self, clear_color: Color) {
-        self.canvas.set_draw_color(clear_color);
-        self.canvas.clear();
+        self.canvas_mut().set_draw_color(clear_color);
+        self.canvas_mut().clear();
     }
 
-    /// Updates the screen,
+    /// Updates the screen. If a frame limit is set, waits until enough time has passed since
+    /// the previous frame.
     pub fn present(&mut self) {
-        self.canvas.present();
+        if let Some(limit) = self.frame_limit {
+            let frame_time = Duration::from_secs_f32(1.0 / limit as f32);
+            if let Some(remaining) = frame_time.checked_sub(self.last_present.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+
+        self.canvas_mut().present();
+        self.last_present = Instant::now();
+        self.fps.tick();
+    }
+
+    /// Limits the number of presented frames per second, `None` removes the limit. Useful when
+    /// vsync is disabled.
+    pub fn set_frame_limit(&mut self, frame_limit: Option<u32>) {
+        self.frame_limit = frame_limit.filter(|&limit| limit > 0);
+    }
+
+    /// Returns the maximal number of presented frames per second.
+    pub fn frame_limit(&self) -> Option<u32> {
+        self.frame_limit
+    }
+
+    /// Returns the smoothed number of presented frames per second.
+    pub fn fps(&self) -> f32 {
+        self.fps.rate()
+    }
+
+    /// Returns the camera applied by the drawing helpers.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns the camera applied by the drawing helpers mutably.
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Draws a line between two points.
+    pub fn draw_line(&mut self, from: Vector2f, to: Vector2f, color: Color) -> Result<(), Error> {
+        let (from, to) = (self.to_point(from), self.to_point(to));
+
+        self.canvas_mut().set_draw_color(color);
+        self.canvas_mut().draw_line(from, to)?;
+
+        Ok(())
+    }
+
+    /// Draws lines connecting the given points in order.
+    pub fn draw_polyline(&mut self, points: &[Vector2f], color: Color) -> Result<(), Error> {
+        let points: Vec<Point> = points.iter().map(|&p| self.to_point(p)).collect();
+
+        self.canvas_mut().set_draw_color(color);
+        self.canvas_mut().draw_lines(&points[..])?;
+
+        Ok(())
+    }
+
+    /// Draws the outline of the given box.
+    pub fn draw_rect_outline(&mut self, aabb: &AABBf, color: Color) -> Result<(), Error> {
+        let rect = self.to_rect(aabb);
+
+        self.canvas_mut().set_draw_color(color);
+        self.canvas_mut().draw_rect(rect)?;
+
+        Ok(())
+    }
+
+    /// Fills the given box.
+    pub fn fill_rect(&mut self, aabb: &AABBf, color: Color) -> Result<(), Error> {
+        let rect = self.to_rect(aabb);
+
+        self.canvas_mut().set_draw_color(color);
+        self.canvas_mut().fill_rect(rect)?;
+
+        Ok(())
+    }
+
+    /// Fills the given box, blending the color with what is already drawn
+    /// according to its alpha.
+    pub fn fill_rect_blended(&mut self, aabb: &AABBf, color: Color) -> Result<(), Error> {
+        let previous = self.canvas().blend_mode();
+        self.canvas_mut().set_blend_mode(BlendMode::Blend);
+        let res = self.fill_rect(aabb, color);
+        self.canvas_mut().set_blend_mode(previous);
+
+        res
+    }
+
+    /// Draws the outline of a circle.
+    pub fn draw_circle(
+        &mut self,
+        center: Vector2f,
+        radius: f32,
+        color: Color,
+    ) -> Result<(), Error> {
+        // Roughly one segment per 4 pixels of the circumference.
+        let segments = ((radius * self.camera.zoom * std::f32::consts::PI / 2.0) as usize).max(12);
+
+        let points: Vec<Vector2f> = (0..=segments)
+            .map(|i| {
+                let angle = i as f32 / segments as f32 * std::f32::consts::PI * 2.0;
+                center + Vector2f::from_coords(angle.cos(), angle.sin()) * radius
+            })
+            .collect();
+
+        self.draw_polyline(&points, color)
+    }
+
+    /// Fills a circle.
+    pub fn fill_circle(
+        &mut self,
+        center: Vector2f,
+        radius: f32,
+        color: Color,
+    ) -> Result<(), Error> {
+        let center = self.camera.world_to_screen(center);
+        let radius = radius * self.camera.zoom;
+
+        self.canvas_mut().set_draw_color(color);
+
+        // The circle is filled row by row in screen pixels.
+        let rows = radius as i32;
+        for dy in -rows..=rows {
+            let half_width = (radius * radius - (dy * dy) as f32).sqrt();
+            let y = (center.y + dy as f32) as i32;
+
+            self.canvas_mut().draw_line(
+                Point::new((center.x - half_width) as i32, y),
+                Point::new((center.x + half_width) as i32, y),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn to_point(&self, v: Vector2f) -> Point {
+        let v = self.camera.world_to_screen(v).as_vector2i();
+
+        Point::new(v.x, v.y)
+    }
+
+    fn to_rect(&self, aabb: &AABBf) -> Rect {
+        let pos = self.camera.world_to_screen(aabb.min).as_vector2i();
+        let size = (aabb.size() * self.camera.zoom).as_vector2u();
+
+        Rect::new(pos.x, pos.y, size.x, size.y)
     }
 
     /// Checks whether the given key is pressed.
@@ -118,6 +660,95 @@ impl GameWindow {
         self.pressed_keys.contains(key_code)
     }
 
+    /// Checks whether the given key has been pressed down during the last [`poll`]. Unlike
+    /// [`is_key_pressed`], this is true only once per key press.
+    ///
+    /// [`poll`]: #method.poll
+    /// [`is_key_pressed`]: #method.is_key_pressed
+    pub fn was_key_just_pressed(&self, key_code: &Keycode) -> bool {
+        self.just_pressed_keys.contains(key_code)
+    }
+
+    /// Checks whether the given key has been released during the last [`poll`].
+    ///
+    /// [`poll`]: #method.poll
+    pub fn was_key_just_released(&self, key_code: &Keycode) -> bool {
+        self.just_released_keys.contains(key_code)
+    }
+
+    /// Starts collecting typed text with an empty buffer. Key presses are still reported as
+    /// usual.
+    pub fn start_text_input(&mut self) {
+        self.text_input.clear();
+        self.text_input_util.start();
+    }
+
+    /// Stops collecting typed text. The collected text stays available until the next
+    /// [`start_text_input`].
+    ///
+    /// [`start_text_input`]: #method.start_text_input
+    pub fn stop_text_input(&mut self) {
+        self.text_input_util.stop();
+    }
+
+    /// Returns true while typed text is being collected.
+    pub fn is_text_input_active(&self) -> bool {
+        self.text_input_util.is_active()
+    }
+
+    /// Returns the text typed since the last [`start_text_input`]. Backspace removes the last
+    /// character.
+    ///
+    /// [`start_text_input`]: #method.start_text_input
+    pub fn text_input(&self) -> &str {
+        &self.text_input
+    }
+
+    /// Returns true when at least one game controller is connected.
+    pub fn has_controller(&self) -> bool {
+        !self.controllers.is_empty()
+    }
+
+    /// Checks whether the given button is pressed on any connected game controller.
+    pub fn is_button_pressed(&self, button: &Button) -> bool {
+        self.pressed_buttons.contains(button)
+    }
+
+    /// Returns the position of the given axis of the first connected game controller in
+    /// `[-1, 1]`, or zero if there is none. Triggers range from `0` to `1`.
+    pub fn controller_axis(&self, axis: Axis) -> f32 {
+        self.controllers
+            .first()
+            .map(|c| (c.axis(axis) as f32 / i16::MAX as f32).max(-1.0))
+            .unwrap_or(0.0)
+    }
+
+    /// Returns true when the window has been resized during the last [`poll`]. The new size is
+    /// available in [`config`].
+    ///
+    /// [`poll`]: #method.poll
+    /// [`config`]: #method.config
+    pub fn was_resized(&self) -> bool {
+        self.resized
+    }
+
+    /// Sets a device independent resolution for rendering. Everything drawn on the canvas is then
+    /// scaled to the size of the window, keeping the aspect ratio.
+    pub fn set_logical_size(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.canvas_mut().set_logical_size(width, height)?;
+
+        Ok(())
+    }
+
+    /// Returns the resolution in which the canvas is drawn. This is the logical size if one has
+    /// been set, the size of the window otherwise.
+    pub fn logical_size(&self) -> (u32, u32) {
+        match self.canvas().logical_size() {
+            (0, 0) => (self.config.width, self.config.height),
+            size => size,
+        }
+    }
+
     /// Returns true when a quit event has been received.
     pub fn should_close(&self) -> bool {
         self.should_close
@@ -137,7 +768,7 @@ impl GameWindow {
     }
 
     /// Returns a reference to [`WindowConfig`] with which this window was
-    /// created.
+    /// created. Width and height follow the current size of the window.
     ///
     /// [`WindowConfig`]: struct.WindowConfig.html
     pub fn config(&self) -> &WindowConfig {
@@ -149,7 +780,7 @@ impl GameWindow {
     /// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
     /// [`Window`]: ../../sdl2/video/struct.Window.html
     pub fn canvas(&self) -> &Canvas<Window> {
-        &self.canvas
+        self.canvas.as_ref().expect("the canvas is missing")
     }
 
     /// Returns a mutable reference to current [`Canvas`] of `SDL2` [`Window`].
@@ -157,30 +788,138 @@ impl GameWindow {
     /// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
     /// [`Window`]: ../../sdl2/video/struct.Window.html
     pub fn canvas_mut(&mut self) -> &mut Canvas<Window> {
-        &mut self.canvas
+        self.canvas.as_mut().expect("the canvas is missing")
+    }
+
+    /// Returns true when presenting a frame waits for the vertical sync of the display.
+    pub fn vsync(&self) -> bool {
+        self.config.vsync
+    }
+
+    /// Enables or disables vertical sync. `SDL2` only supports this when creating a renderer, so
+    /// the canvas is recreated, keeping its logical size.
+    ///
+    /// This fails if any texture, [`TextureCreator`] or [`TextureManager`] of the current canvas
+    /// is still alive, since those keep the previous renderer around. The window is left without a
+    /// canvas then and is marked to close, so nothing may be drawn into it anymore.
+    ///
+    /// [`TextureCreator`]: ../../sdl2/render/struct.TextureCreator.html
+    /// [`TextureManager`]: struct.TextureManager.html
+    pub fn set_vsync(&mut self, vsync: bool) -> Result<(), Error> {
+        if vsync == self.config.vsync {
+            return Ok(());
+        }
+
+        let old_canvas = self.canvas.take().expect("the canvas is missing");
+        let logical_size = old_canvas.logical_size();
+        let window = old_canvas.into_window();
+
+        let canvas = match build_canvas(window, vsync) {
+            Ok(canvas) => canvas,
+            Err(err) => {
+                self.should_close = true;
+                return Err(err);
+            }
+        };
+
+        self.canvas = Some(canvas);
+        self.config.vsync = vsync;
+        if logical_size != (0, 0) {
+            self.canvas_mut()
+                .set_logical_size(logical_size.0, logical_size.1)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn build_canvas(window: Window, vsync: bool) -> Result<Canvas<Window>, Error> {
+    let mut builder = window.into_canvas();
+    if vsync {
+        builder = builder.present_vsync();
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Horizontal anchor of drawn text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    /// The text starts at the given position.
+    #[default]
+    Left,
+
+    /// The text is centered around the given position.
+    Center,
+
+    /// The text ends at the given position.
+    Right,
+}
+
+/// Describes how text is drawn by [`TextRenderer::draw_text_styled`].
+///
+/// [`TextRenderer::draw_text_styled`]: struct.TextRenderer.html#method.draw_text_styled
+#[derive(Debug, Copy, Clone)]
+pub struct TextStyle {
+    /// Scale of the rendered font, see [`DEFAULT_POINT_SIZE`].
+    ///
+    /// [`DEFAULT_POINT_SIZE`]: constant.DEFAULT_POINT_SIZE.html
+    pub scale: f32,
+
+    /// Color of the text.
+    pub color: Color,
+
+    /// Horizontal anchor of every line.
+    pub align: TextAlign,
+
+    /// Multiplier of the font's recommended distance between lines.
+    pub line_spacing: f32,
+
+    /// Lines longer than this width in pixels are wrapped at whitespace.
+    pub wrap_width: Option<u32>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            color: Color::RGB(0, 0, 0),
+            align: TextAlign::Left,
+            line_spacing: 1.0,
+            wrap_width: None,
+        }
     }
 }
 
-/// A helper text renderer for specific `Font`.
+/// A helper text renderer for specific `Font`. Text can be drawn on any [`Canvas`].
+///
+/// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
 pub struct TextRenderer<'a> {
     font: Font<'a, 'a>,
-    texture_creator: TextureCreator<WindowContext>,
 }
 
 impl<'a> TextRenderer<'a> {
-    /// Creates a new text renderer for the given [`Canvas`].
-    ///
-    /// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
-    pub fn new(ttf_context: &'a Sdl2TtfContext, canvas: &Canvas<Window>) -> Result<Self, String> {
-        let mut font = ttf_context.load_font("Inconsolata-Bold.ttf", 128)?;
-        font.set_style(sdl2::ttf::FontStyle::BOLD);
+    /// Creates a new text renderer using the bundled font.
+    pub fn new(ttf_context: &'a Sdl2TtfContext) -> Result<Self, Error> {
+        Self::with_font(ttf_context, FontSource::default(), DEFAULT_POINT_SIZE)
+    }
 
-        let texture_creator = canvas.texture_creator();
+    /// Creates a new text renderer using the font from `source` at `point_size`.
+    pub fn with_font(
+        ttf_context: &'a Sdl2TtfContext,
+        source: FontSource<'a>,
+        point_size: u16,
+    ) -> Result<Self, Error> {
+        let mut font = match source {
+            FontSource::Path(path) => ttf_context.load_font(path, point_size),
+            FontSource::Bytes(bytes) => {
+                ttf_context.load_font_from_rwops(RWops::from_bytes(bytes)?, point_size)
+            }
+        }
+        .map_err(Error::Ttf)?;
+        font.set_style(sdl2::ttf::FontStyle::BOLD);
 
-        Ok(Self {
-            font,
-            texture_creator,
-        })
+        Ok(Self { font })
     }
 
     /// Draws the given text on the [`Canvas`].
@@ -196,12 +935,14 @@ impl<'a> TextRenderer<'a> {
     /// #     title: "Title",
     /// #     width: 1280,
     /// #     height: 720,
+    /// #     resizable: false,
+    /// #     vsync: true,
     /// # };
     /// #
     /// # let mut game_window = GameWindow::new(config).unwrap();
     /// #
     /// let ttf_context = sdl2::ttf::init().unwrap();
-    /// let text_renderer = TextRenderer::new(&ttf_context, game_window.canvas()).unwrap();
+    /// let text_renderer = TextRenderer::new(&ttf_context).unwrap();
     ///
     /// text_renderer.draw_text("Hello", 0, 0, 0.2, game_window.canvas_mut());
     /// ```
@@ -212,25 +953,205 @@ impl<'a> TextRenderer<'a> {
         y: i32,
         scale: f32,
         canvas: &mut Canvas<Window>,
-    ) -> Result<(), String> {
-        let surface = self
-            .font
-            .render(text)
-            .blended(Color::RGBA(0, 0, 0, 255))
-            .map_err(|e| e.to_string())?;
+    ) -> Result<(), Error> {
+        let style = TextStyle {
+            scale,
+            ..Default::default()
+        };
 
-        let texture = self
-            .texture_creator
-            .create_texture_from_surface(&surface)
-            .map_err(|e| e.to_string())?;
+        self.draw_text_styled(text, x, y, &style, canvas)
+    }
+
+    /// Returns the width and height of the given text drawn with `scale`.
+    pub fn measure(&self, text: &str, scale: f32) -> Result<(u32, u32), Error> {
+        let style = TextStyle {
+            scale,
+            ..Default::default()
+        };
+
+        self.measure_styled(text, &style)
+    }
+
+    /// Returns the width and height of the given text drawn with `style`, including all its
+    /// lines.
+    pub fn measure_styled(&self, text: &str, style: &TextStyle) -> Result<(u32, u32), Error> {
+        let lines = self.layout_lines(text, style)?;
+
+        let mut width = 0;
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            width = width.max(self.font.size_of(line)?.0);
+        }
+
+        let line_height = self.line_height(style);
+        let height = if lines.is_empty() {
+            0
+        } else {
+            (lines.len() - 1) as u32 * line_height
+                + (self.font.height() as f32 * style.scale) as u32
+        };
+
+        Ok(((width as f32 * style.scale) as u32, height))
+    }
+
+    /// Draws the given text on the [`Canvas`] as described by `style`. Every line is anchored at
+    /// `x` as described by its alignment, the first line starts at `y`.
+    ///
+    /// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
+    pub fn draw_text_styled(
+        &self,
+        text: &str,
+        x: i32,
+        y: i32,
+        style: &TextStyle,
+        canvas: &mut Canvas<Window>,
+    ) -> Result<(), Error> {
+        let line_height = self.line_height(style) as i32;
+        let texture_creator = canvas.texture_creator();
+
+        for (i, line) in self.layout_lines(text, style)?.iter().enumerate() {
+            // SDL2_ttf cannot render an empty string.
+            if line.is_empty() {
+                continue;
+            }
+
+            let surface = self.font.render(line).blended(style.color)?;
+
+            let texture = texture_creator.create_texture_from_surface(&surface)?;
 
-        let width = surface.width() as f32 * scale;
-        let height = surface.height() as f32 * scale;
+            let width = (surface.width() as f32 * style.scale) as u32;
+            let height = (surface.height() as f32 * style.scale) as u32;
+
+            let x = match style.align {
+                TextAlign::Left => x,
+                TextAlign::Center => x - width as i32 / 2,
+                TextAlign::Right => x - width as i32,
+            };
+            let y = y + i as i32 * line_height;
+
+            canvas.copy(&texture, None, Some(Rect::new(x, y, width, height)))?;
+        }
+
+        Ok(())
+    }
+
+    fn line_height(&self, style: &TextStyle) -> u32 {
+        (self.font.recommended_line_spacing() as f32 * style.scale * style.line_spacing) as u32
+    }
+
+    /// Splits the text into lines at `\n` and wraps them at whitespace to fit the wrap width
+    /// of `style`. A single word wider than the wrap width gets a line of its own.
+    fn layout_lines(&self, text: &str, style: &TextStyle) -> Result<Vec<String>, Error> {
+        let wrap_width = match style.wrap_width {
+            Some(wrap_width) => wrap_width as f32 / style.scale,
+            None => return Ok(text.lines().map(String::from).collect()),
+        };
+
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let candidate = if line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", line, word)
+                };
+
+                if !line.is_empty() && self.font.size_of(&candidate)?.0 as f32 > wrap_width {
+                    lines.push(line);
+                    line = word.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+
+            lines.push(line);
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Mirroring of a drawn sprite.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Flip {
+    /// Mirrors the sprite left to right.
+    pub horizontal: bool,
+
+    /// Mirrors the sprite upside down.
+    pub vertical: bool,
+}
+
+/// Loads images into textures and keeps them under a key, so that sprites can be drawn by name.
+///
+/// BMP images can always be loaded, other formats such as PNG require the `image` feature.
+pub struct TextureManager<'a> {
+    texture_creator: &'a TextureCreator<WindowContext>,
+    textures: HashMap<String, Texture<'a>>,
+}
+
+impl<'a> TextureManager<'a> {
+    /// Creates an empty manager creating textures with `texture_creator`.
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> Self {
+        Self {
+            texture_creator,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Loads the image at `path` and stores it under `key`, replacing any texture previously
+    /// stored under the same key.
+    pub fn load<P: AsRef<Path>>(&mut self, key: &str, path: P) -> Result<(), Error> {
+        let texture = self.load_texture(path.as_ref())?;
+        self.textures.insert(key.to_string(), texture);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    fn load_texture(&self, path: &Path) -> Result<Texture<'a>, Error> {
+        use sdl2::image::LoadTexture;
+
+        Ok(self.texture_creator.load_texture(path)?)
+    }
+
+    #[cfg(not(feature = "image"))]
+    fn load_texture(&self, path: &Path) -> Result<Texture<'a>, Error> {
+        let surface = sdl2::surface::Surface::load_bmp(path)?;
+
+        Ok(self.texture_creator.create_texture_from_surface(&surface)?)
+    }
+
+    /// Returns the texture stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&Texture<'a>> {
+        self.textures.get(key)
+    }
+
+    /// Draws the `src` part of the texture stored under `key`, or all of it if `src` is `None`,
+    /// into `dst` on the [`Canvas`]. The sprite is rotated clockwise by `rotation` degrees around
+    /// the center of `dst`.
+    ///
+    /// [`Canvas`]: ../../sdl2/render/struct.Canvas.html
+    pub fn draw_sprite(
+        &self,
+        key: &str,
+        src: Option<Rect>,
+        dst: Rect,
+        flip: Flip,
+        rotation: f64,
+        canvas: &mut Canvas<Window>,
+    ) -> Result<(), Error> {
+        let texture = self
+            .get(key)
+            .ok_or_else(|| Error::Sdl(format!("no texture loaded as {:?}", key)))?;
 
-        canvas.copy(
-            &texture,
+        canvas.copy_ex(
+            texture,
+            src,
+            dst,
+            rotation,
             None,
-            Some(Rect::new(x, y, width as u32, height as u32)),
+            flip.horizontal,
+            flip.vertical,
         )?;
 
         Ok(())